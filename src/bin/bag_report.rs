@@ -0,0 +1,50 @@
+use {
+    aoc::{io::file::get_file_contents, luggage::bag_registry::BagRegistry},
+    std::{collections::BTreeMap, env, io::Result},
+};
+
+fn print_distribution(title: &str, distribution: &BTreeMap<usize, usize>) {
+    println!("{}:", title);
+    for (edges, bags) in distribution {
+        println!("  {:>3} -> {} bags", edges, bags);
+    }
+}
+
+fn main() -> Result<()> {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "data/day07.txt".to_string());
+    let file_contents = get_file_contents(&path)?;
+    let (bag_registry, warnings) = BagRegistry::try_from_lenient(&file_contents)?;
+    let statistics = bag_registry.statistics();
+
+    println!("Bags = {}", bag_registry.len());
+    for warning in warnings.iter() {
+        println!("Warning, {}", warning);
+    }
+
+    match statistics.longest_chain() {
+        Some(chain) => {
+            println!("Max depth = {}", statistics.max_depth().unwrap());
+            println!("Longest chain = {}", chain.join(" -> "));
+        }
+        None => println!("Max depth = unbounded"),
+    }
+    println!(
+        "Roots ({}) = {}",
+        statistics.roots().len(),
+        statistics.roots().join(", ")
+    );
+    println!(
+        "Leaves ({}) = {}",
+        statistics.leaves().len(),
+        statistics.leaves().join(", ")
+    );
+    print_distribution("Fan in", statistics.fan_in());
+    print_distribution("Fan out", statistics.fan_out());
+    for cycle in statistics.cycles() {
+        println!("Cycle = {}", cycle.join(", "));
+    }
+
+    Ok(())
+}
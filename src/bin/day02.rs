@@ -1,33 +1,24 @@
 use {
-    aoc::{
-        io::file::get_file_contents,
-        password::{password::Password, policy::Scheme},
+    aoc::password::{
+        policy::{OccurrenceCount, PositionCheck},
+        stream::StreamValidator,
+    },
+    std::{
+        fs::File,
+        io::{BufReader, Result},
     },
-    std::io::Result,
 };
 
 fn main() -> Result<()> {
-    let passwords = get_file_contents("data/day02.txt")?
-        .trim()
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect::<Vec<Password>>();
+    let reader = BufReader::new(File::open("data/day02.txt")?);
+    let mut validator = StreamValidator::new(vec![&OccurrenceCount, &PositionCheck]);
+    let summary = validator.validate(reader)?.strict()?;
+
+    println!("Part 1 = {}", summary.valid()[0]);
 
-    println!(
-        "Part 1 = {}",
-        passwords
-            .iter()
-            .filter(|password| password.is_valid(Scheme::OccurrenceCount))
-            .count()
-    );
+    println!("Part 2 = {}", summary.valid()[1]);
 
-    println!(
-        "Part 2 = {}",
-        passwords
-            .iter()
-            .filter(|password| password.is_valid(Scheme::PositionCheck))
-            .count()
-    );
+    println!("Throughput = {}", summary);
 
     Ok(())
 }
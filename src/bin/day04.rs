@@ -1,25 +1,30 @@
 use {
-    aoc::{io::file::get_file_contents, passport::PassportValidation},
+    aoc::{
+        io::file::get_file_contents,
+        passport::{policy::FieldPolicy, reader::PassportReader, PassportValidation},
+    },
     std::io::Result,
 };
 
-fn get_passport_validations() -> Result<Box<[PassportValidation]>> {
+fn get_passport_validations(policy: FieldPolicy) -> Result<Box<[PassportValidation]>> {
     let input = get_file_contents("data/day04.txt")?;
-    let mut current_passport_validation = PassportValidation::default();
     let mut passport_validations = Vec::new();
-    for line in input.trim().lines() {
-        if line.is_empty() {
-            passport_validations.push(current_passport_validation);
-            current_passport_validation = PassportValidation::default();
-        } else {
-            current_passport_validation.merge(line.parse()?);
+    for record in PassportReader::new(input.as_bytes()) {
+        let record = record?;
+        let mut passport_validation = PassportValidation::default();
+        for line in record.text().lines() {
+            let line = PassportValidation::parse_with_policy(line, policy);
+            passport_validation
+                .merge_with_policy(line.map_err(|error| record.locate(error))?, policy)
+                .map_err(|error| record.locate(error))?;
         }
+        passport_validations.push(passport_validation);
     }
     Ok(passport_validations.into_boxed_slice())
 }
 
 fn main() -> Result<()> {
-    let passport_validations = get_passport_validations()?;
+    let passport_validations = get_passport_validations(FieldPolicy::default())?;
 
     println!(
         "Part 1 = {}",
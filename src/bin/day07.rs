@@ -11,7 +11,7 @@ fn main() -> Result<()> {
         "Part 1 = {}",
         bag_registry.find_containers("shiny gold").count()
     );
-    println!("Part 2 = {}", bag_registry.count_nested("shiny gold"));
+    println!("Part 2 = {}", bag_registry.try_count_nested("shiny gold")?);
 
     Ok(())
 }
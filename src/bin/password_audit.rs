@@ -0,0 +1,22 @@
+use {
+    aoc::{
+        io::file::get_file_contents,
+        password::audit::{Audit, Format},
+    },
+    std::{
+        env,
+        io::{self, Result},
+    },
+};
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| "data/day02.txt".to_string());
+    let format = match args.next() {
+        Some(format) => format.parse()?,
+        None => Format::Text,
+    };
+    let file_contents = get_file_contents(&path)?;
+    let audit = Audit::new(&file_contents);
+    audit.write(&mut io::stdout().lock(), format)
+}
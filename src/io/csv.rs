@@ -0,0 +1,22 @@
+// a CSV field, quoted if it holds a separator, a quote or a line break.
+pub fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape(""), "");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("two\r\nlines"), "\"two\r\nlines\"");
+    }
+}
@@ -46,8 +46,35 @@ where
     }
 }
 
+// like Chunks, but collects each chunk so that iterators which can't be cloned (such as the lines
+// of a BufRead) can be chunked too.
+pub struct ChunkVecs<I, P> {
+    iter: I,
+    predicate: P,
+}
+
+impl<I: Iterator, P: FnMut(&<I as Iterator>::Item) -> bool> Iterator for ChunkVecs<I, P> {
+    type Item = Vec<<I as Iterator>::Item>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::new();
+        for item in self.iter.by_ref() {
+            if (self.predicate)(&item) {
+                chunk.push(item);
+            } else if !chunk.is_empty() {
+                return Some(chunk);
+            }
+        }
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
 pub trait ProvideChunks: Iterator + Sized {
     fn chunks<P>(self, predicate: P) -> Chunks<Self, P>;
+    fn chunk_vecs<P>(self, predicate: P) -> ChunkVecs<Self, P>;
 }
 
 impl<T: Iterator> ProvideChunks for T {
@@ -57,6 +84,13 @@ impl<T: Iterator> ProvideChunks for T {
             predicate,
         }
     }
+
+    fn chunk_vecs<P>(self, predicate: P) -> ChunkVecs<Self, P> {
+        ChunkVecs {
+            iter: self,
+            predicate,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -110,4 +144,14 @@ mod tests {
         assert!(chunk.next().is_none());
         assert!(chunks.next().is_none());
     }
+
+    #[test]
+    fn test_chunk_vecs() {
+        const INPUT: &str = "\nabc\ndef\n\n\nhi";
+        let chunks = INPUT
+            .lines()
+            .chunk_vecs(|line: &&str| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(chunks, vec![vec!["abc", "def"], vec!["hi"]]);
+    }
 }
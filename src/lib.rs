@@ -4,8 +4,11 @@ pub mod game_console;
 pub mod io;
 pub mod iter;
 pub mod luggage;
+pub mod num;
 pub mod passport;
 pub mod password;
+pub mod pattern;
+pub mod text;
 pub mod toboggan;
 pub mod xmas;
 
@@ -1,2 +1,3 @@
 pub mod bag;
 pub mod bag_registry;
+pub mod properties;
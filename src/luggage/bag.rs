@@ -1,5 +1,6 @@
 use std::{
     convert::TryFrom,
+    fmt::{self, Display, Formatter},
     io::{Error, ErrorKind},
 };
 
@@ -10,12 +11,47 @@ pub struct Bag<'a> {
 }
 
 impl<'a> Bag<'a> {
+    pub fn new(name: &'a str, contents: Box<[(u32, &'a str)]>) -> Self {
+        Self { name, contents }
+    }
+
     pub fn name(&self) -> &'a str {
-        &self.name
+        self.name
     }
 
     pub fn contents(&self) -> &[(u32, &'a str)] {
-        &*self.contents
+        &self.contents
+    }
+}
+
+// the order of a bag's contents doesn't change what it holds.
+impl PartialEq for Bag<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let sorted = |bag: &Self| {
+            let mut contents = bag.contents.to_vec();
+            contents.sort_unstable_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(&b.0)));
+            contents
+        };
+        self.name == other.name
+            && self.contents.len() == other.contents.len()
+            && sorted(self) == sorted(other)
+    }
+}
+
+impl Display for Bag<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} bags contain ", self.name)?;
+        if self.contents.is_empty() {
+            return write!(f, "no other bags.");
+        }
+        for (index, (count, name)) in self.contents.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            let suffix = if *count == 1 { "bag" } else { "bags" };
+            write!(f, "{} {} {}", count, name, suffix)?;
+        }
+        write!(f, ".")
     }
 }
 
@@ -24,6 +60,13 @@ impl<'a> TryFrom<&'a str> for Bag<'a> {
 
     fn try_from(from: &'a str) -> Result<Self, Self::Error> {
         const SEARCH_STRING: &str = " bags contain ";
+        let invalid = |reason| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("String invalid for conversion to Bag, {}: {}", reason, from),
+            )
+        };
+
         if let Some(index) = from.find(SEARCH_STRING) {
             let name = &from[..index];
             let rest = &from[index + SEARCH_STRING.len()..];
@@ -33,27 +76,18 @@ impl<'a> TryFrom<&'a str> for Bag<'a> {
                 if let Some(index) = substr.find(" bag") {
                     let substr = &substr[..index];
                     if substr != "no other" {
-                        if let Some(index) = substr.find(" ") {
+                        if let Some(index) = substr.find(' ') {
                             if let Ok(count) = substr[..index].parse() {
                                 contents.push((count, &substr[index + 1..]));
                             } else {
-                                return Err(Error::new(
-                                    ErrorKind::InvalidData,
-                                    format!("String invalid for conversion to Bag: {}", from),
-                                ));
+                                return Err(invalid("invalid bag count"));
                             }
                         } else {
-                            return Err(Error::new(
-                                ErrorKind::InvalidData,
-                                format!("String invalid for conversion to Bag: {}", from),
-                            ));
+                            return Err(invalid("missing bag count"));
                         }
                     }
                 } else {
-                    return Err(Error::new(
-                        ErrorKind::InvalidData,
-                        format!("String invalid for conversion to Bag: {}", from),
-                    ));
+                    return Err(invalid("contents should end with 'bag' or 'bags'"));
                 }
             }
 
@@ -62,10 +96,7 @@ impl<'a> TryFrom<&'a str> for Bag<'a> {
                 contents: contents.into_boxed_slice(),
             })
         } else {
-            Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("String invalid for conversion to Bag: {}", from),
-            ))
+            Err(invalid("missing 'bags contain'"))
         }
     }
 }
@@ -100,4 +131,37 @@ mod tests {
         assert_eq!(bag.name, "muted yellow");
         assert_eq!(*bag.contents, [(2, "shiny gold"), (9, "faded blue")]);
     }
+
+    #[test]
+    fn test_display_round_trip() {
+        const INPUT: &str = "muted yellow bags contain 1 shiny gold bag, 9 faded blue bags.";
+        let bag = Bag::try_from(INPUT).unwrap();
+        assert_eq!(format!("{}", bag), INPUT);
+        let bag = Bag::try_from("dotted black bags contain no other bags.").unwrap();
+        assert_eq!(
+            format!("{}", bag),
+            "dotted black bags contain no other bags."
+        );
+    }
+
+    #[test]
+    fn test_equality_ignores_contents_order() {
+        let bag = Bag::try_from("muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.");
+        let reordered =
+            Bag::try_from("muted yellow bags contain 9 faded blue bags, 2 shiny gold bags.");
+        assert_eq!(bag.unwrap(), reordered.unwrap());
+        let bag = Bag::try_from("muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.");
+        let changed =
+            Bag::try_from("muted yellow bags contain 9 shiny gold bags, 2 faded blue bags.");
+        assert_ne!(bag.unwrap(), changed.unwrap());
+    }
+
+    #[test]
+    fn test_invalid_bag_count() {
+        let bag = Bag::try_from("muted yellow bags contain two shiny gold bags.");
+        assert_eq!(
+            format!("{}", bag.unwrap_err()),
+            "String invalid for conversion to Bag, invalid bag count: muted yellow bags contain two shiny gold bags."
+        );
+    }
 }
@@ -1,13 +1,23 @@
+pub mod bill_of_materials;
+pub mod capacity;
+pub mod common;
+pub mod count;
+pub mod diff;
+pub mod format;
+pub mod parse;
+pub mod paths;
+pub mod statistics;
+
 use {
     crate::luggage::bag::Bag,
     std::{
         collections::HashMap,
         convert::TryFrom,
-        io::{Error, ErrorKind},
+        io::{Error, ErrorKind, Result},
     },
 };
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct BagRegistry<'a> {
     mapping: HashMap<&'a str, Bag<'a>>,
 }
@@ -17,6 +27,12 @@ impl<'a> BagRegistry<'a> {
         self.mapping.len()
     }
 
+    fn get(&self, name: &str) -> Result<&Bag<'a>> {
+        self.mapping
+            .get(name)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Unknown bag: {}", name)))
+    }
+
     pub fn find_containers(&'a self, needle: &'a str) -> impl Iterator<Item = &'a str> {
         self.mapping
             .keys()
@@ -50,33 +66,8 @@ impl<'a> BagRegistry<'a> {
 impl<'a> TryFrom<&'a str> for BagRegistry<'a> {
     type Error = Error;
 
-    fn try_from(string: &'a str) -> Result<Self, Self::Error> {
-        let mapping = string
-            .trim()
-            .lines()
-            .map(|line| Bag::try_from(line))
-            .filter(|result| result.is_ok())
-            .map(|result| {
-                let result = result.unwrap();
-                (result.name(), result)
-            })
-            .collect::<HashMap<&'a str, Bag<'a>>>();
-
-        for (_, bag) in &mapping {
-            for (_, contains) in bag.contents() {
-                if !mapping.contains_key(contains) {
-                    return Err(Error::new(
-                        ErrorKind::InvalidData,
-                        format!(
-                            "Incomplete bag registry data, missing contents of bag: {}",
-                            contains
-                        ),
-                    ));
-                }
-            }
-        }
-
-        Ok(Self { mapping })
+    fn try_from(string: &'a str) -> Result<Self> {
+        Self::try_from_lenient(string).map(|(registry, _)| registry)
     }
 }
 
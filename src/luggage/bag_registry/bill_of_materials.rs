@@ -0,0 +1,175 @@
+use {
+    super::BagRegistry,
+    std::{
+        collections::BTreeMap,
+        io::{Error, ErrorKind, Result},
+    },
+};
+
+#[derive(Debug)]
+pub struct BillOfMaterials<'a> {
+    name: &'a str,
+    totals: BTreeMap<&'a str, u64>,
+    leaves: BTreeMap<&'a str, u64>,
+    levels: Box<[BTreeMap<&'a str, u64>]>, // levels[0] holds the bags directly inside the outermost bag.
+    count: u64,
+}
+
+impl<'a> BillOfMaterials<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn totals(&self) -> &BTreeMap<&'a str, u64> {
+        &self.totals
+    }
+
+    pub fn leaves(&self) -> &BTreeMap<&'a str, u64> {
+        &self.leaves
+    }
+
+    pub fn levels(&self) -> &[BTreeMap<&'a str, u64>] {
+        &self.levels
+    }
+
+    pub fn total(&self, name: &str) -> u64 {
+        self.totals.get(name).copied().unwrap_or(0)
+    }
+
+    // the number of bags inside the outermost bag, checked for overflow when the bill was built.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl<'a> BagRegistry<'a> {
+    pub fn bill_of_materials(&self, outermost_name: &str) -> Result<BillOfMaterials<'a>> {
+        let name = self.get(outermost_name)?.name();
+        let overflow = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Overflow expanding bill of materials for bag: {}", name),
+            )
+        };
+
+        // expand one level at a time, carrying the multiplied counts down to the next level.
+        let mut levels = Vec::new();
+        let mut current = BTreeMap::new();
+        current.insert(name, 1u64);
+        loop {
+            let mut next = BTreeMap::new();
+            for (bag, count) in &current {
+                for (number, contains) in self.mapping[bag].contents() {
+                    let entry = next.entry(*contains).or_insert(0u64);
+                    *entry = count
+                        .checked_mul(*number as u64)
+                        .and_then(|nested| entry.checked_add(nested))
+                        .ok_or_else(overflow)?;
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            if levels.len() == self.len() {
+                // any chain longer than the number of distinct bags must revisit a bag.
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Bag contains itself, bill of materials is unbounded: {}",
+                        name
+                    ),
+                ));
+            }
+            levels.push(next.clone());
+            current = next;
+        }
+
+        let mut totals = BTreeMap::new();
+        for level in &levels {
+            for (bag, count) in level {
+                let entry = totals.entry(*bag).or_insert(0u64);
+                *entry = entry.checked_add(*count).ok_or_else(overflow)?;
+            }
+        }
+        let count = totals
+            .values()
+            .try_fold(0u64, |count, total| count.checked_add(*total))
+            .ok_or_else(overflow)?;
+        let leaves = totals
+            .iter()
+            .filter(|(bag, _)| self.mapping[*bag].contents().is_empty())
+            .map(|(bag, count)| (*bag, *count))
+            .collect();
+
+        Ok(BillOfMaterials {
+            name,
+            totals,
+            leaves,
+            levels: levels.into_boxed_slice(),
+            count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::convert::TryFrom};
+
+    const INPUT: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.\ndark orange bags contain 3 bright white bags, 4 muted yellow bags.\nbright white bags contain 1 shiny gold bag.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.\ndark olive bags contain 3 faded blue bags, 4 dotted black bags.\nvibrant plum bags contain 5 faded blue bags, 6 dotted black bags.\nfaded blue bags contain no other bags.\ndotted black bags contain no other bags.";
+
+    #[test]
+    fn test_totals_and_leaves() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let bill = registry.bill_of_materials("shiny gold").unwrap();
+        assert_eq!(bill.count(), registry.count_nested("shiny gold") as u64);
+        assert_eq!(bill.total("dark olive"), 1);
+        assert_eq!(bill.total("vibrant plum"), 2);
+        assert_eq!(bill.total("faded blue"), 13);
+        assert_eq!(bill.total("dotted black"), 16);
+        assert_eq!(bill.leaves().len(), 2);
+        assert_eq!(bill.leaves()["faded blue"], 13);
+        assert!(!bill.leaves().contains_key("vibrant plum"));
+    }
+
+    #[test]
+    fn test_levels() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let bill = registry.bill_of_materials("light red").unwrap();
+        let levels = bill.levels();
+        assert_eq!(levels.len(), 4);
+        assert_eq!(levels[0]["muted yellow"], 2);
+        assert_eq!(levels[1]["shiny gold"], 5);
+        assert_eq!(levels[1]["faded blue"], 18);
+        assert_eq!(levels[2]["vibrant plum"], 10);
+        assert_eq!(levels[3]["faded blue"], 65);
+        assert_eq!(bill.total("faded blue"), 83);
+    }
+
+    #[test]
+    fn test_empty_and_invalid() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let bill = registry.bill_of_materials("faded blue").unwrap();
+        assert!(bill.levels().is_empty());
+        assert_eq!(bill.count(), 0);
+        assert!(registry.bill_of_materials("unknown").is_err());
+
+        let registry = BagRegistry::try_from(
+            "shiny gold bags contain 1 dark red bag.\ndark red bags contain 1 shiny gold bag.",
+        )
+        .unwrap();
+        assert!(registry.bill_of_materials("shiny gold").is_err());
+    }
+
+    #[test]
+    fn test_count_overflow_is_an_error() {
+        // each total fits in a u64, but their sum doesn't.
+        let registry = BagRegistry::try_from("shiny gold bags contain 4294967295 dark red bags, 4294967295 dark blue bags.\ndark red bags contain 4294967295 faded blue bags.\ndark blue bags contain 4294967295 dotted black bags.\nfaded blue bags contain no other bags.\ndotted black bags contain no other bags.").unwrap();
+        assert_eq!(
+            registry
+                .bill_of_materials("shiny gold")
+                .unwrap_err()
+                .to_string(),
+            "Overflow expanding bill of materials for bag: shiny gold"
+        );
+    }
+}
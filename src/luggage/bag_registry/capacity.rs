@@ -0,0 +1,139 @@
+use {
+    super::BagRegistry,
+    crate::luggage::properties::{Properties, PropertyTable},
+    std::{
+        collections::{HashMap, HashSet},
+        io::{Error, ErrorKind, Result},
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CapacityLimit {
+    Weight(f64),
+    Volume(f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation<'a> {
+    name: &'a str,
+    packed: Properties,
+}
+
+impl<'a> Violation<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn packed(&self) -> Properties {
+        self.packed
+    }
+}
+
+impl<'a> BagRegistry<'a> {
+    pub fn packed_properties(&self, name: &str, table: &PropertyTable) -> Result<Properties> {
+        self.packed_memoized(name, table, &mut HashMap::new(), &mut HashSet::new())
+    }
+
+    pub fn capacity_violations(
+        &self,
+        table: &PropertyTable,
+        limit: CapacityLimit,
+    ) -> Result<Box<[Violation<'a>]>> {
+        let mut memo = HashMap::new();
+        let mut violations = Vec::new();
+        for name in self.mapping.keys() {
+            let packed = self.packed_memoized(name, table, &mut memo, &mut HashSet::new())?;
+            let exceeded = match limit {
+                CapacityLimit::Weight(max) => packed.weight() > max,
+                CapacityLimit::Volume(max) => packed.volume() > max,
+            };
+            if exceeded {
+                violations.push(Violation { name, packed });
+            }
+        }
+        violations.sort_by(|a, b| a.name.cmp(b.name));
+        Ok(violations.into_boxed_slice())
+    }
+
+    // same shape as count_nested, but every bag contributes its own properties rather than 1.
+    fn packed_memoized(
+        &self,
+        name: &str,
+        table: &PropertyTable,
+        memo: &mut HashMap<&'a str, Properties>,
+        visiting: &mut HashSet<&'a str>,
+    ) -> Result<Properties> {
+        let bag = self.get(name)?;
+        if let Some(packed) = memo.get(bag.name()) {
+            return Ok(*packed);
+        }
+        if !visiting.insert(bag.name()) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Bag contains itself, packed weight is unbounded: {}", name),
+            ));
+        }
+
+        let mut packed = table.get(bag.name())?;
+        for (number, contains) in bag.contents() {
+            packed = packed + self.packed_memoized(contains, table, memo, visiting)? * *number;
+        }
+
+        visiting.remove(bag.name());
+        memo.insert(bag.name(), packed);
+        Ok(packed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::convert::TryFrom};
+
+    const INPUT: &str = "shiny gold bags contain 2 dark red bags.\ndark red bags contain 2 dark orange bags.\ndark orange bags contain no other bags.";
+    const PROPERTIES: &str = "shiny gold,1,10\ndark red,0.5,4\ndark orange,0.25,1";
+
+    #[test]
+    fn test_packed_properties() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let table = PropertyTable::try_from(PROPERTIES).unwrap();
+        let packed = registry.packed_properties("shiny gold", &table).unwrap();
+        assert_eq!(packed, Properties::new(3.0, 22.0).unwrap());
+        assert_eq!(
+            registry.packed_properties("dark orange", &table).unwrap(),
+            Properties::new(0.25, 1.0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_capacity_violations() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let table = PropertyTable::try_from(PROPERTIES).unwrap();
+        let violations = registry
+            .capacity_violations(&table, CapacityLimit::Weight(1.0))
+            .unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].name(), "shiny gold");
+        assert_eq!(violations[0].packed().weight(), 3.0);
+
+        let violations = registry
+            .capacity_violations(&table, CapacityLimit::Volume(5.0))
+            .unwrap();
+        let names = violations.iter().map(|v| v.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["dark red", "shiny gold"]);
+    }
+
+    #[test]
+    fn test_missing_properties() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let table = PropertyTable::try_from("shiny gold,1,10\ndark red,0.5,4").unwrap();
+        assert_eq!(
+            format!(
+                "{}",
+                registry
+                    .packed_properties("shiny gold", &table)
+                    .unwrap_err()
+            ),
+            "Missing properties for bag: dark orange"
+        );
+    }
+}
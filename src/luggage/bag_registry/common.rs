@@ -0,0 +1,204 @@
+use {
+    super::BagRegistry,
+    std::{
+        collections::{HashMap, HashSet, VecDeque},
+        io::Result,
+    },
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommonContainer<'a> {
+    name: &'a str,
+    distance: usize,
+}
+
+impl<'a> CommonContainer<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    // the number of containment steps to the furthest of the required bags.
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+}
+
+impl<'a> BagRegistry<'a> {
+    pub fn common_containers(&self, needles: &[&str]) -> Result<Box<[CommonContainer<'a>]>> {
+        self.rank_common(needles, false)
+    }
+
+    pub fn minimal_common_containers(&self, needles: &[&str]) -> Result<Box<[&'a str]>> {
+        Ok(self.keep_minimal(&self.rank_common(needles, false)?))
+    }
+
+    pub fn least_common_ancestors(&self, needles: &[&str]) -> Result<Box<[&'a str]>> {
+        Ok(self.keep_minimal(&self.rank_common(needles, true)?))
+    }
+
+    fn rank_common(&self, needles: &[&str], reflexive: bool) -> Result<Box<[CommonContainer<'a>]>> {
+        let reverse = self.reverse_mapping();
+        let mut common: Option<HashMap<&'a str, usize>> = None;
+        for needle in needles {
+            let needle = self.get(needle)?.name();
+            let mut ancestors = Self::ancestors(&reverse, needle);
+            if reflexive {
+                ancestors.insert(needle, 0);
+            }
+            common = Some(match common {
+                None => ancestors,
+                Some(common) => common
+                    .into_iter()
+                    .filter_map(|(name, distance)| {
+                        ancestors
+                            .get(name)
+                            .map(|other| (name, distance.max(*other)))
+                    })
+                    .collect(),
+            });
+        }
+
+        let mut ranked = common
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, distance)| CommonContainer { name, distance })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| a.distance.cmp(&b.distance).then(a.name.cmp(b.name)));
+        Ok(ranked.into_boxed_slice())
+    }
+
+    // drops every candidate that contains another candidate, keeping the ranked order. with cyclic
+    // rules bags in the same cycle contain each other, so neither is dropped for the other.
+    fn keep_minimal(&self, candidates: &[CommonContainer<'a>]) -> Box<[&'a str]> {
+        let reverse = self.reverse_mapping();
+        let candidate_ancestors = candidates
+            .iter()
+            .map(|candidate| Self::ancestors(&reverse, candidate.name))
+            .collect::<Vec<_>>();
+        let mut dominated: HashSet<&str> = HashSet::new();
+        for (candidate, ancestors) in candidates.iter().zip(&candidate_ancestors) {
+            for (other, other_ancestors) in candidates.iter().zip(&candidate_ancestors) {
+                if other.name != candidate.name
+                    && ancestors.contains_key(other.name)
+                    && !other_ancestors.contains_key(candidate.name)
+                {
+                    dominated.insert(other.name);
+                }
+            }
+        }
+        candidates
+            .iter()
+            .map(|candidate| candidate.name)
+            .filter(|name| !dominated.contains(name))
+            .collect()
+    }
+
+    pub(super) fn reverse_mapping(&self) -> HashMap<&'a str, Vec<&'a str>> {
+        let mut reverse = HashMap::with_capacity(self.len());
+        for (name, bag) in &self.mapping {
+            for (_, contains) in bag.contents() {
+                reverse
+                    .entry(*contains)
+                    .or_insert_with(Vec::new)
+                    .push(*name);
+            }
+        }
+        reverse
+    }
+
+    pub(super) fn ancestors(
+        reverse: &HashMap<&'a str, Vec<&'a str>>,
+        name: &'a str,
+    ) -> HashMap<&'a str, usize> {
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((name, 0));
+        while let Some((current, distance)) = queue.pop_front() {
+            for container in reverse.get(current).into_iter().flatten() {
+                if !distances.contains_key(container) {
+                    distances.insert(*container, distance + 1);
+                    queue.push_back((*container, distance + 1));
+                }
+            }
+        }
+        distances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::convert::TryFrom};
+
+    const INPUT: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.\ndark orange bags contain 3 bright white bags, 4 muted yellow bags.\nbright white bags contain 1 shiny gold bag.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.\ndark olive bags contain 3 faded blue bags, 4 dotted black bags.\nvibrant plum bags contain 5 faded blue bags, 6 dotted black bags.\nfaded blue bags contain no other bags.\ndotted black bags contain no other bags.";
+
+    #[test]
+    fn test_common_containers_are_ranked() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let common = registry
+            .common_containers(&["dark olive", "vibrant plum"])
+            .unwrap();
+        let names = common.iter().map(|c| c.name()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "shiny gold",
+                "bright white",
+                "muted yellow",
+                "dark orange",
+                "light red"
+            ]
+        );
+        assert_eq!(common[0].distance(), 1);
+        assert_eq!(common[4].distance(), 3);
+    }
+
+    #[test]
+    fn test_minimal_common_containers() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        assert_eq!(
+            &*registry
+                .minimal_common_containers(&["shiny gold", "faded blue"])
+                .unwrap(),
+            ["muted yellow", "bright white"]
+        );
+        assert_eq!(
+            &*registry
+                .minimal_common_containers(&["bright white", "muted yellow"])
+                .unwrap(),
+            ["dark orange", "light red"]
+        );
+        assert!(registry
+            .minimal_common_containers(&["light red", "dark orange"])
+            .unwrap()
+            .is_empty());
+        assert!(registry.minimal_common_containers(&["unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_least_common_ancestors() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        assert_eq!(
+            &*registry
+                .least_common_ancestors(&["shiny gold", "faded blue"])
+                .unwrap(),
+            ["shiny gold"]
+        );
+        assert_eq!(
+            &*registry
+                .least_common_ancestors(&["bright white", "muted yellow"])
+                .unwrap(),
+            ["dark orange", "light red"]
+        );
+    }
+
+    #[test]
+    fn test_minimal_common_containers_with_cycles() {
+        let registry = BagRegistry::try_from("light red bags contain 1 shiny gold bag.\nshiny gold bags contain 1 dark red bag, 1 faded blue bag, 1 dotted black bag.\ndark red bags contain 1 shiny gold bag.\nfaded blue bags contain no other bags.\ndotted black bags contain no other bags.").unwrap();
+        assert_eq!(
+            &*registry
+                .minimal_common_containers(&["faded blue", "dotted black"])
+                .unwrap(),
+            ["shiny gold", "dark red"]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use {
+    super::BagRegistry,
+    crate::num::count::Count,
+    std::{
+        collections::{HashMap, HashSet},
+        io::{Error, ErrorKind, Result},
+    },
+};
+
+impl<'a> BagRegistry<'a> {
+    pub fn try_count_nested(&self, outermost_name: &str) -> Result<u32> {
+        self.try_count_nested_as(outermost_name)
+    }
+
+    pub fn try_count_nested_as<T: Count>(&self, outermost_name: &str) -> Result<T> {
+        self.count_nested_memoized(outermost_name, &mut HashMap::new(), &mut HashSet::new())
+    }
+
+    fn count_nested_memoized<T: Count>(
+        &self,
+        name: &str,
+        memo: &mut HashMap<&'a str, T>,
+        visiting: &mut HashSet<&'a str>,
+    ) -> Result<T> {
+        let bag = self.get(name)?;
+        if let Some(count) = memo.get(bag.name()) {
+            return Ok(count.clone());
+        }
+        if !visiting.insert(bag.name()) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Bag contains itself, nested count is unbounded: {}", name),
+            ));
+        }
+
+        let mut count = T::zero();
+        for (number, contains) in bag.contents() {
+            let nested = self.count_nested_memoized(contains, memo, visiting)?;
+            count = T::from_u32(1)
+                .checked_add(&nested)
+                .and_then(|nested| nested.checked_mul(&T::from_u32(*number)))
+                .and_then(|nested| count.checked_add(&nested))
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Overflow counting nested bags inside bag: {}", name),
+                    )
+                })?;
+        }
+
+        visiting.remove(bag.name());
+        memo.insert(bag.name(), count.clone());
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::num::big_uint::BigUint, std::convert::TryFrom};
+
+    const DEEP: &str = "shiny gold bags contain 65536 dark red bags.\ndark red bags contain 65536 dark orange bags.\ndark orange bags contain 65536 dark yellow bags.\ndark yellow bags contain 65536 dark green bags.\ndark green bags contain 65536 dark blue bags.\ndark blue bags contain no other bags.";
+
+    #[test]
+    fn test_try_count_nested() {
+        let registry = BagRegistry::try_from("shiny gold bags contain 2 dark red bags.\ndark red bags contain 2 dark orange bags.\ndark orange bags contain no other bags.").unwrap();
+        assert_eq!(registry.try_count_nested("shiny gold").unwrap(), 6);
+        assert_eq!(registry.count_nested("shiny gold"), 6);
+        assert!(registry.try_count_nested("unknown").is_err());
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        let registry = BagRegistry::try_from(DEEP).unwrap();
+        assert_eq!(
+            format!("{}", registry.try_count_nested("shiny gold").unwrap_err()),
+            "Overflow counting nested bags inside bag: dark yellow"
+        );
+        assert_eq!(
+            registry.try_count_nested_as::<u64>("dark orange").unwrap(),
+            281479271743488
+        );
+        assert!(registry.try_count_nested_as::<u64>("shiny gold").is_err());
+    }
+
+    #[test]
+    fn test_big_uint_count() {
+        let registry = BagRegistry::try_from(DEEP).unwrap();
+        let count = registry
+            .try_count_nested_as::<BigUint>("shiny gold")
+            .unwrap();
+        assert_eq!(format!("{}", count), "1208944266640182156001280");
+        assert_eq!(
+            registry.try_count_nested_as::<u128>("shiny gold").unwrap(),
+            1208944266640182156001280
+        );
+    }
+
+    #[test]
+    fn test_cycle_is_an_error() {
+        let registry = BagRegistry::try_from(
+            "shiny gold bags contain 1 dark red bag.\ndark red bags contain 1 shiny gold bag.",
+        )
+        .unwrap();
+        assert!(registry.try_count_nested("shiny gold").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use {
+    super::BagRegistry,
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fmt::{self, Display, Formatter},
+        io::{Error, ErrorKind, Result},
+    },
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdgeChange<'a> {
+    container: &'a str,
+    contained: &'a str,
+    old: Option<u32>,
+    new: Option<u32>,
+}
+
+impl<'a> EdgeChange<'a> {
+    pub fn container(&self) -> &'a str {
+        self.container
+    }
+
+    pub fn contained(&self) -> &'a str {
+        self.contained
+    }
+
+    pub fn old_count(&self) -> Option<u32> {
+        self.old
+    }
+
+    pub fn new_count(&self) -> Option<u32> {
+        self.new
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainersChange<'a> {
+    name: &'a str,
+    gained: Box<[&'a str]>,
+    lost: Box<[&'a str]>,
+}
+
+impl<'a> ContainersChange<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn gained(&self) -> &[&'a str] {
+        &self.gained
+    }
+
+    pub fn lost(&self) -> &[&'a str] {
+        &self.lost
+    }
+}
+
+// a count of None means the nested count couldn't be computed, e.g. due to a cycle or overflow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NestedCountChange<'a> {
+    name: &'a str,
+    old: Option<u64>,
+    new: Option<u64>,
+}
+
+impl<'a> NestedCountChange<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn old_count(&self) -> Option<u64> {
+        self.old
+    }
+
+    pub fn new_count(&self) -> Option<u64> {
+        self.new
+    }
+}
+
+#[derive(Debug)]
+pub struct RegistryDiff<'a> {
+    added_bags: Box<[&'a str]>,
+    removed_bags: Box<[&'a str]>,
+    added_edges: Box<[EdgeChange<'a>]>,
+    removed_edges: Box<[EdgeChange<'a>]>,
+    changed_counts: Box<[EdgeChange<'a>]>,
+    changed_containers: Box<[ContainersChange<'a>]>,
+    changed_nested_counts: Box<[NestedCountChange<'a>]>,
+}
+
+impl<'a> RegistryDiff<'a> {
+    pub fn added_bags(&self) -> &[&'a str] {
+        &self.added_bags
+    }
+
+    pub fn removed_bags(&self) -> &[&'a str] {
+        &self.removed_bags
+    }
+
+    pub fn added_edges(&self) -> &[EdgeChange<'a>] {
+        &self.added_edges
+    }
+
+    pub fn removed_edges(&self) -> &[EdgeChange<'a>] {
+        &self.removed_edges
+    }
+
+    pub fn changed_counts(&self) -> &[EdgeChange<'a>] {
+        &self.changed_counts
+    }
+
+    pub fn changed_containers(&self) -> &[ContainersChange<'a>] {
+        &self.changed_containers
+    }
+
+    pub fn changed_nested_counts(&self) -> &[NestedCountChange<'a>] {
+        &self.changed_nested_counts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_bags.is_empty()
+            && self.removed_bags.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_counts.is_empty()
+    }
+}
+
+impl<'a> BagRegistry<'a> {
+    pub fn diff(&self, other: &BagRegistry<'a>) -> Result<RegistryDiff<'a>> {
+        let old_names = self.mapping.keys().copied().collect::<BTreeSet<_>>();
+        let new_names = other.mapping.keys().copied().collect::<BTreeSet<_>>();
+        let old_edges = self.edges()?;
+        let new_edges = other.edges()?;
+
+        let mut added_edges = Vec::new();
+        let mut removed_edges = Vec::new();
+        let mut changed_counts = Vec::new();
+        for (&(container, contained), &old) in &old_edges {
+            match new_edges.get(&(container, contained)) {
+                None => removed_edges.push(EdgeChange {
+                    container,
+                    contained,
+                    old: Some(old),
+                    new: None,
+                }),
+                Some(&new) if new != old => changed_counts.push(EdgeChange {
+                    container,
+                    contained,
+                    old: Some(old),
+                    new: Some(new),
+                }),
+                _ => {}
+            }
+        }
+        for (&(container, contained), &new) in &new_edges {
+            if !old_edges.contains_key(&(container, contained)) {
+                added_edges.push(EdgeChange {
+                    container,
+                    contained,
+                    old: None,
+                    new: Some(new),
+                });
+            }
+        }
+
+        // query answers are only compared for bags that exist on both sides, as added and removed
+        // bags are already reported above.
+        let old_reverse = self.reverse_mapping();
+        let new_reverse = other.reverse_mapping();
+        let mut changed_containers = Vec::new();
+        let mut changed_nested_counts = Vec::new();
+        for name in old_names.intersection(&new_names) {
+            let old_containers = Self::ancestors(&old_reverse, name)
+                .into_keys()
+                .collect::<BTreeSet<_>>();
+            let new_containers = Self::ancestors(&new_reverse, name)
+                .into_keys()
+                .collect::<BTreeSet<_>>();
+            if old_containers != new_containers {
+                changed_containers.push(ContainersChange {
+                    name,
+                    gained: new_containers
+                        .difference(&old_containers)
+                        .copied()
+                        .collect(),
+                    lost: old_containers
+                        .difference(&new_containers)
+                        .copied()
+                        .collect(),
+                });
+            }
+
+            let old = self.try_count_nested_as::<u64>(name).ok();
+            let new = other.try_count_nested_as::<u64>(name).ok();
+            if old != new {
+                changed_nested_counts.push(NestedCountChange { name, old, new });
+            }
+        }
+
+        Ok(RegistryDiff {
+            added_bags: new_names.difference(&old_names).copied().collect(),
+            removed_bags: old_names.difference(&new_names).copied().collect(),
+            added_edges: added_edges.into_boxed_slice(),
+            removed_edges: removed_edges.into_boxed_slice(),
+            changed_counts: changed_counts.into_boxed_slice(),
+            changed_containers: changed_containers.into_boxed_slice(),
+            changed_nested_counts: changed_nested_counts.into_boxed_slice(),
+        })
+    }
+
+    // a rule may list the same colour more than once, those counts are summed.
+    fn edges(&self) -> Result<BTreeMap<(&'a str, &'a str), u32>> {
+        let mut edges = BTreeMap::new();
+        for (name, bag) in &self.mapping {
+            for (number, contains) in bag.contents() {
+                let count = edges.entry((*name, *contains)).or_insert(0u32);
+                *count = count.checked_add(*number).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Overflow summing {} bags in bag: {}", contains, name),
+                    )
+                })?;
+            }
+        }
+        Ok(edges)
+    }
+}
+
+fn format_count(count: Option<u64>) -> String {
+    count.map_or_else(|| "unavailable".to_string(), |count| count.to_string())
+}
+
+impl Display for RegistryDiff<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for name in self.added_bags.iter() {
+            writeln!(f, "+ bag {}", name)?;
+        }
+        for name in self.removed_bags.iter() {
+            writeln!(f, "- bag {}", name)?;
+        }
+        for edge in self.added_edges.iter() {
+            writeln!(
+                f,
+                "+ {} contains {} {}",
+                edge.container,
+                edge.new.unwrap(),
+                edge.contained
+            )?;
+        }
+        for edge in self.removed_edges.iter() {
+            writeln!(
+                f,
+                "- {} contains {} {}",
+                edge.container,
+                edge.old.unwrap(),
+                edge.contained
+            )?;
+        }
+        for edge in self.changed_counts.iter() {
+            writeln!(
+                f,
+                "~ {} contains {} -> {} {}",
+                edge.container,
+                edge.old.unwrap(),
+                edge.new.unwrap(),
+                edge.contained
+            )?;
+        }
+        for change in self.changed_containers.iter() {
+            writeln!(
+                f,
+                "~ containers of {}: gained [{}], lost [{}]",
+                change.name,
+                change.gained.join(", "),
+                change.lost.join(", ")
+            )?;
+        }
+        for change in self.changed_nested_counts.iter() {
+            writeln!(
+                f,
+                "~ nested count of {}: {} -> {}",
+                change.name,
+                format_count(change.old),
+                format_count(change.new)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::convert::TryFrom};
+
+    const OLD: &str = "light red bags contain 1 bright white bag.\nbright white bags contain 2 shiny gold bags.\nshiny gold bags contain no other bags.\nfaded blue bags contain 1 shiny gold bag.";
+    const NEW: &str = "light red bags contain 1 bright white bag, 1 shiny gold bag.\nbright white bags contain 3 shiny gold bags.\nshiny gold bags contain no other bags.\ndotted black bags contain no other bags.";
+
+    #[test]
+    fn test_structural_diff() {
+        let old = BagRegistry::try_from(OLD).unwrap();
+        let new = BagRegistry::try_from(NEW).unwrap();
+        let diff = old.diff(&new).unwrap();
+        assert_eq!(diff.added_bags(), ["dotted black"]);
+        assert_eq!(diff.removed_bags(), ["faded blue"]);
+        assert_eq!(diff.added_edges().len(), 1);
+        assert_eq!(diff.added_edges()[0].container(), "light red");
+        assert_eq!(diff.added_edges()[0].contained(), "shiny gold");
+        assert_eq!(diff.removed_edges()[0].container(), "faded blue");
+        assert_eq!(diff.changed_counts()[0].old_count(), Some(2));
+        assert_eq!(diff.changed_counts()[0].new_count(), Some(3));
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_query_impact() {
+        let old = BagRegistry::try_from(OLD).unwrap();
+        let new = BagRegistry::try_from(NEW).unwrap();
+        let diff = old.diff(&new).unwrap();
+        assert_eq!(diff.changed_containers().len(), 1);
+        assert_eq!(diff.changed_containers()[0].name(), "shiny gold");
+        assert!(diff.changed_containers()[0].gained().is_empty());
+        assert_eq!(diff.changed_containers()[0].lost(), ["faded blue"]);
+        let counts = diff.changed_nested_counts();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].name(), "bright white");
+        assert_eq!(
+            (counts[1].old_count(), counts[1].new_count()),
+            (Some(3), Some(5))
+        );
+    }
+
+    #[test]
+    fn test_identical_registries() {
+        let old = BagRegistry::try_from(OLD).unwrap();
+        let new = BagRegistry::try_from(OLD).unwrap();
+        let diff = old.diff(&new).unwrap();
+        assert!(diff.is_empty());
+        assert!(diff.changed_nested_counts().is_empty());
+        assert_eq!(format!("{}", diff), "");
+    }
+
+    #[test]
+    fn test_repeated_colour_overflow_is_an_error() {
+        let old = BagRegistry::try_from(OLD).unwrap();
+        let new = BagRegistry::try_from("shiny gold bags contain 4294967295 dark red bags, 1 dark red bag.\ndark red bags contain no other bags.").unwrap();
+        assert_eq!(
+            old.diff(&new).unwrap_err().to_string(),
+            "Overflow summing dark red bags in bag: shiny gold"
+        );
+        let new = BagRegistry::try_from("shiny gold bags contain 2 dark red bags, 1 dark red bag.\ndark red bags contain no other bags.").unwrap();
+        let diff = new.diff(&old).unwrap();
+        assert_eq!(diff.removed_edges()[0].old_count(), Some(3));
+    }
+}
@@ -0,0 +1,73 @@
+use {
+    super::BagRegistry,
+    crate::luggage::bag::Bag,
+    std::fmt::{self, Display, Formatter, Write},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContentsOrder {
+    AsParsed,
+    ByName,
+}
+
+impl<'a> BagRegistry<'a> {
+    pub fn format_rules(&self, contents_order: ContentsOrder) -> String {
+        let mut names = self.mapping.keys().copied().collect::<Vec<_>>();
+        names.sort_unstable();
+
+        let mut output = String::new();
+        for name in names {
+            let bag = &self.mapping[name];
+            match contents_order {
+                ContentsOrder::AsParsed => writeln!(output, "{}", bag),
+                ContentsOrder::ByName => writeln!(output, "{}", sorted_contents(bag)),
+            }
+            .unwrap(); // writing into a String can't fail.
+        }
+        output
+    }
+}
+
+fn sorted_contents<'a>(bag: &Bag<'a>) -> Bag<'a> {
+    let mut contents = bag.contents().to_vec();
+    contents.sort_by(|a, b| a.1.cmp(b.1));
+    Bag::new(bag.name(), contents.into_boxed_slice())
+}
+
+// the canonical form: rules sorted by bag name, contents sorted by bag name.
+impl Display for BagRegistry<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.format_rules(ContentsOrder::ByName))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::convert::TryFrom};
+
+    const INPUT: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.\nbright white bags contain 1 shiny gold bag.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain no other bags.\nfaded blue bags contain no other bags.";
+
+    #[test]
+    fn test_format_as_parsed_round_trip() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let output = registry.format_rules(ContentsOrder::AsParsed);
+        assert_eq!(output, "bright white bags contain 1 shiny gold bag.\nfaded blue bags contain no other bags.\nlight red bags contain 1 bright white bag, 2 muted yellow bags.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain no other bags.\n");
+        assert_eq!(BagRegistry::try_from(&output as &str).unwrap(), registry);
+    }
+
+    #[test]
+    fn test_canonical_form_is_stable() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let canonical = format!("{}", registry);
+        assert!(
+            canonical.contains("muted yellow bags contain 9 faded blue bags, 2 shiny gold bags.\n")
+        );
+        let reparsed = BagRegistry::try_from(&canonical as &str).unwrap();
+        assert_eq!(reparsed, registry);
+        assert_eq!(format!("{}", reparsed), canonical);
+        assert_eq!(
+            reparsed.count_nested("light red"),
+            registry.count_nested("light red")
+        );
+    }
+}
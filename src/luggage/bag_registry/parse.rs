@@ -0,0 +1,188 @@
+use {
+    super::BagRegistry,
+    crate::luggage::bag::Bag,
+    std::{
+        collections::HashMap,
+        convert::TryFrom,
+        fmt::{self, Display, Formatter},
+        io::{Error, ErrorKind, Result},
+    },
+};
+
+#[derive(Debug, PartialEq)]
+pub struct RuleError<'a> {
+    line_number: usize,
+    line: &'a str,
+    reason: String,
+}
+
+impl<'a> RuleError<'a> {
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn line(&self) -> &'a str {
+        self.line
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl Display for RuleError<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line_number, self.reason)
+    }
+}
+
+struct ParsedRules<'a> {
+    mapping: HashMap<&'a str, Bag<'a>>,
+    order: Vec<(usize, &'a str, &'a str)>, // line number, line and bag name of each accepted rule.
+    errors: Vec<RuleError<'a>>,
+}
+
+fn parse_rules(string: &str) -> ParsedRules<'_> {
+    let mut mapping = HashMap::new();
+    let mut order = Vec::new();
+    let mut errors = Vec::new();
+
+    // line numbers are 1-based and count blank lines so they match what an editor shows.
+    for (line_number, line) in (1..).zip(string.lines()) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match Bag::try_from(line) {
+            Ok(bag) => {
+                let name = bag.name();
+                if mapping.insert(name, bag).is_some() {
+                    order.retain(|(_, _, existing)| *existing != name);
+                    errors.push(RuleError {
+                        line_number,
+                        line,
+                        reason: format!("Duplicate rule for bag, replacing earlier rule: {}", name),
+                    });
+                }
+                order.push((line_number, line, name));
+            }
+            Err(error) => errors.push(RuleError {
+                line_number,
+                line,
+                reason: error.to_string(),
+            }),
+        }
+    }
+
+    ParsedRules {
+        mapping,
+        order,
+        errors,
+    }
+}
+
+impl<'a> BagRegistry<'a> {
+    pub fn try_from_strict(string: &'a str) -> std::result::Result<Self, Box<[RuleError<'a>]>> {
+        let ParsedRules {
+            mapping,
+            order,
+            mut errors,
+        } = parse_rules(string);
+
+        for (line_number, line, name) in order {
+            for (_, contains) in mapping[name].contents() {
+                if !mapping.contains_key(contains) {
+                    errors.push(RuleError {
+                        line_number,
+                        line,
+                        reason: format!("Missing rule for contained bag: {}", contains),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Self { mapping })
+        } else {
+            errors.sort_by_key(|error| error.line_number);
+            Err(errors.into_boxed_slice())
+        }
+    }
+
+    pub fn try_from_lenient(string: &'a str) -> Result<(Self, Box<[RuleError<'a>]>)> {
+        let ParsedRules {
+            mapping,
+            order,
+            errors,
+        } = parse_rules(string);
+
+        for (_, _, name) in order {
+            for (_, contains) in mapping[name].contents() {
+                if !mapping.contains_key(contains) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Incomplete bag registry data, missing contents of bag: {}",
+                            contains
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok((Self { mapping }, errors.into_boxed_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "light red bags contain 1 bright white bag.\nbright white bags contain one shiny gold bag.\n\nshiny gold bags contain no other bags.\ndark orange bags contain 3 faded blue bags.";
+
+    #[test]
+    fn test_strict_reports_every_malformed_line() {
+        let errors = BagRegistry::try_from_strict(INPUT).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].line_number(), 1);
+        assert_eq!(
+            errors[0].reason(),
+            "Missing rule for contained bag: bright white"
+        );
+        assert_eq!(errors[1].line_number(), 2);
+        assert_eq!(
+            errors[1].line(),
+            "bright white bags contain one shiny gold bag."
+        );
+        assert_eq!(errors[2].line_number(), 5);
+        assert_eq!(
+            format!("{}", errors[2]),
+            "line 5: Missing rule for contained bag: faded blue"
+        );
+    }
+
+    #[test]
+    fn test_strict_accepts_valid_rules() {
+        let registry = BagRegistry::try_from_strict(
+            "light red bags contain 1 bright white bag.\nbright white bags contain no other bags.",
+        )
+        .unwrap();
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_lenient_returns_warnings() {
+        let (registry, warnings) = BagRegistry::try_from_lenient(
+            "light red bags contain 1 bright white bag.\nbright white bags contain no other bags.\nlight red bags contain 2 bright white bags.\nfaded blue bags contain many bags.",
+        )
+        .unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.count_nested("light red"), 2);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            format!("{}", warnings[0]),
+            "line 3: Duplicate rule for bag, replacing earlier rule: light red"
+        );
+        assert_eq!(warnings[1].line_number(), 4);
+    }
+}
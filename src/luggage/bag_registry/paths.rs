@@ -0,0 +1,306 @@
+use {
+    super::BagRegistry,
+    std::{
+        collections::{HashMap, VecDeque},
+        io::{Error, ErrorKind, Result},
+    },
+};
+
+fn overflow(outer: &str, inner: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Overflow multiplying bags from {} to {}", outer, inner),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainmentPath<'a> {
+    bags: Box<[&'a str]>,
+    multiplicity: u32,
+}
+
+impl<'a> ContainmentPath<'a> {
+    pub fn bags(&self) -> &[&'a str] {
+        &self.bags
+    }
+
+    pub fn multiplicity(&self) -> u32 {
+        self.multiplicity
+    }
+
+    pub fn len(&self) -> usize {
+        self.bags.len() - 1 // number of containment steps rather than number of bags.
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct ContainmentPaths<'a> {
+    paths: Box<[ContainmentPath<'a>]>,
+    total: u32,
+}
+
+impl<'a> ContainmentPaths<'a> {
+    pub fn iter(&self) -> impl Iterator<Item = &ContainmentPath<'a>> {
+        self.paths.iter()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+impl<'a> BagRegistry<'a> {
+    pub fn containment_paths(&self, outer: &str, inner: &str) -> Result<ContainmentPaths<'a>> {
+        let mut paths = Vec::new();
+        if let (Some(outer), Some(inner)) = (self.mapping.get(outer), self.mapping.get(inner)) {
+            // only bags which can reach inner are worth descending into.
+            let reaching = Self::ancestors(&self.reverse_mapping(), inner.name());
+            if reaching.contains_key(outer.name()) {
+                let mut current = vec![outer.name()];
+                self.collect_paths(&mut current, Some(1), inner.name(), &reaching, &mut paths)?;
+            }
+        }
+        let total = paths
+            .iter()
+            .try_fold(0u32, |total, path| total.checked_add(path.multiplicity))
+            .ok_or_else(|| overflow(outer, inner))?;
+        Ok(ContainmentPaths {
+            paths: paths.into_boxed_slice(),
+            total,
+        })
+    }
+
+    // the multiplicity is None once it has overflowed, which is only an error if that path reaches
+    // inner.
+    fn collect_paths(
+        &self,
+        current: &mut Vec<&'a str>,
+        multiplicity: Option<u32>,
+        inner: &str,
+        reaching: &HashMap<&'a str, usize>,
+        paths: &mut Vec<ContainmentPath<'a>>,
+    ) -> Result<()> {
+        let name = current[current.len() - 1];
+        for (number, contains) in self.mapping[name].contents() {
+            if current.contains(contains) {
+                continue; // only follow simple paths so that cyclic rules can't recurse forever.
+            }
+            if *contains != inner && !reaching.contains_key(contains) {
+                continue;
+            }
+            let multiplicity =
+                multiplicity.and_then(|multiplicity| multiplicity.checked_mul(*number));
+            current.push(contains);
+            if *contains == inner {
+                paths.push(ContainmentPath {
+                    bags: current.clone().into_boxed_slice(),
+                    multiplicity: multiplicity.ok_or_else(|| overflow(current[0], inner))?,
+                });
+            } else {
+                self.collect_paths(current, multiplicity, inner, reaching, paths)?;
+            }
+            current.pop();
+        }
+        Ok(())
+    }
+
+    pub fn shortest_containment_path(
+        &self,
+        outer: &str,
+        inner: &str,
+    ) -> Result<Option<ContainmentPath<'a>>> {
+        let outer = match self.mapping.get(outer) {
+            Some(bag) => bag.name(),
+            None => return Ok(None),
+        };
+        let mut previous = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(outer);
+
+        // breadth first search, remembering how we reached each bag so the path can be rebuilt backwards.
+        while let Some(name) = queue.pop_front() {
+            for (number, contains) in self.mapping[name].contents() {
+                if *contains == outer || previous.contains_key(contains) {
+                    continue;
+                }
+                previous.insert(*contains, (name, *number));
+                if *contains == inner {
+                    let mut bags = vec![*contains];
+                    let mut multiplicity = 1u32;
+                    let mut current = *contains;
+                    while let Some((parent, number)) = previous.get(current) {
+                        bags.push(parent);
+                        multiplicity = multiplicity
+                            .checked_mul(*number)
+                            .ok_or_else(|| overflow(outer, inner))?;
+                        current = parent;
+                    }
+                    bags.reverse();
+                    return Ok(Some(ContainmentPath {
+                        bags: bags.into_boxed_slice(),
+                        multiplicity,
+                    }));
+                }
+                queue.push_back(contains);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn max_multiplicity_path(
+        &self,
+        outer: &str,
+        inner: &str,
+    ) -> Result<Option<ContainmentPath<'a>>> {
+        let mut max: Option<ContainmentPath<'a>> = None;
+        for path in self.containment_paths(outer, inner)?.paths.into_vec() {
+            match &max {
+                Some(max) if max.multiplicity >= path.multiplicity => {}
+                _ => max = Some(path),
+            }
+        }
+        Ok(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::convert::TryFrom};
+
+    const INPUT: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.\ndark orange bags contain 3 bright white bags, 4 muted yellow bags.\nbright white bags contain 1 shiny gold bag.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.\ndark olive bags contain 3 faded blue bags, 4 dotted black bags.\nvibrant plum bags contain 5 faded blue bags, 6 dotted black bags.\nfaded blue bags contain no other bags.\ndotted black bags contain no other bags.";
+
+    #[test]
+    fn test_containment_paths() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let paths = registry
+            .containment_paths("light red", "shiny gold")
+            .unwrap();
+        assert_eq!(paths.len(), 2);
+        let mut iter = paths.iter();
+        let path = iter.next().unwrap();
+        assert_eq!(path.bags(), ["light red", "bright white", "shiny gold"]);
+        assert_eq!(path.multiplicity(), 1);
+        let path = iter.next().unwrap();
+        assert_eq!(path.bags(), ["light red", "muted yellow", "shiny gold"]);
+        assert_eq!(path.multiplicity(), 4);
+        assert_eq!(paths.total(), 5);
+    }
+
+    #[test]
+    fn test_no_containment_paths() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        assert!(registry
+            .containment_paths("shiny gold", "light red")
+            .unwrap()
+            .is_empty());
+        assert!(registry
+            .containment_paths("unknown", "shiny gold")
+            .unwrap()
+            .is_empty());
+        assert!(registry
+            .shortest_containment_path("shiny gold", "light red")
+            .unwrap()
+            .is_none());
+        assert!(registry
+            .max_multiplicity_path("faded blue", "dotted black")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_shortest_and_max_multiplicity_paths() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let shortest = registry
+            .shortest_containment_path("light red", "faded blue")
+            .unwrap()
+            .unwrap();
+        assert_eq!(shortest.bags(), ["light red", "muted yellow", "faded blue"]);
+        assert_eq!(shortest.multiplicity(), 18);
+        assert_eq!(shortest.len(), 2);
+
+        let max = registry
+            .max_multiplicity_path("light red", "faded blue")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            max.bags(),
+            [
+                "light red",
+                "muted yellow",
+                "shiny gold",
+                "vibrant plum",
+                "faded blue"
+            ]
+        );
+        assert_eq!(max.multiplicity(), 40);
+        assert_eq!(
+            registry
+                .containment_paths("light red", "faded blue")
+                .unwrap()
+                .total(),
+            83
+        );
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        let registry = BagRegistry::try_from("shiny gold bags contain 65536 dark red bags.\ndark red bags contain 65536 dark orange bags.\ndark orange bags contain no other bags.").unwrap();
+        let error = "Overflow multiplying bags from shiny gold to dark orange";
+        assert_eq!(
+            registry
+                .containment_paths("shiny gold", "dark orange")
+                .unwrap_err()
+                .to_string(),
+            error
+        );
+        assert_eq!(
+            registry
+                .shortest_containment_path("shiny gold", "dark orange")
+                .unwrap_err()
+                .to_string(),
+            error
+        );
+        assert_eq!(
+            registry
+                .containment_paths("shiny gold", "dark red")
+                .unwrap()
+                .total(),
+            65536
+        );
+    }
+
+    #[test]
+    fn test_overflow_off_the_path_is_ignored() {
+        let registry = BagRegistry::try_from("shiny gold bags contain 65536 dark red bags, 1 dark olive bag.\ndark red bags contain 65536 dotted black bags.\ndark olive bags contain 3 faded blue bags.\ndotted black bags contain no other bags.\nfaded blue bags contain no other bags.").unwrap();
+        let paths = registry
+            .containment_paths("shiny gold", "faded blue")
+            .unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths.total(), 3);
+        let max = registry
+            .max_multiplicity_path("shiny gold", "faded blue")
+            .unwrap()
+            .unwrap();
+        assert_eq!(max.bags(), ["shiny gold", "dark olive", "faded blue"]);
+        assert_eq!(
+            registry
+                .shortest_containment_path("shiny gold", "faded blue")
+                .unwrap()
+                .unwrap()
+                .multiplicity(),
+            3
+        );
+    }
+}
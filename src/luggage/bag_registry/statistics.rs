@@ -0,0 +1,274 @@
+use {
+    super::BagRegistry,
+    std::collections::{BTreeMap, HashMap, HashSet},
+};
+
+#[derive(Debug)]
+pub struct Statistics<'a> {
+    longest_chain: Option<Box<[&'a str]>>,
+    fan_in: BTreeMap<usize, usize>,
+    fan_out: BTreeMap<usize, usize>,
+    roots: Box<[&'a str]>,
+    leaves: Box<[&'a str]>,
+    cycles: Box<[Box<[&'a str]>]>,
+}
+
+impl<'a> Statistics<'a> {
+    // nesting is unbounded when the rules contain cycles, so there is no depth to report.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.longest_chain
+            .as_ref()
+            .map(|chain| chain.len().saturating_sub(1))
+    }
+
+    pub fn longest_chain(&self) -> Option<&[&'a str]> {
+        self.longest_chain.as_deref()
+    }
+
+    // maps the number of distinct containers of a bag to how many bags have that many.
+    pub fn fan_in(&self) -> &BTreeMap<usize, usize> {
+        &self.fan_in
+    }
+
+    // maps the number of distinct colours inside a bag to how many bags have that many.
+    pub fn fan_out(&self) -> &BTreeMap<usize, usize> {
+        &self.fan_out
+    }
+
+    pub fn roots(&self) -> &[&'a str] {
+        &self.roots
+    }
+
+    pub fn leaves(&self) -> &[&'a str] {
+        &self.leaves
+    }
+
+    pub fn cycles(&self) -> &[Box<[&'a str]>] {
+        &self.cycles
+    }
+}
+
+struct Tarjan<'a> {
+    index: usize,
+    indices: HashMap<&'a str, usize>,
+    low_links: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Box<[&'a str]>>,
+}
+
+impl<'a> BagRegistry<'a> {
+    pub fn statistics(&self) -> Statistics<'a> {
+        let mut names = self.mapping.keys().copied().collect::<Vec<_>>();
+        names.sort_unstable();
+
+        let mut containers = names
+            .iter()
+            .map(|name| (*name, HashSet::new()))
+            .collect::<HashMap<_, _>>();
+        for name in &names {
+            for (_, contains) in self.mapping[name].contents() {
+                containers.get_mut(contains).unwrap().insert(*name);
+            }
+        }
+
+        let mut fan_in = BTreeMap::new();
+        let mut fan_out = BTreeMap::new();
+        for name in &names {
+            *fan_in.entry(containers[name].len()).or_insert(0) += 1;
+            *fan_out.entry(self.distinct_contents(name)).or_insert(0) += 1;
+        }
+
+        let roots = names
+            .iter()
+            .copied()
+            .filter(|name| containers[name].is_empty())
+            .collect();
+        let leaves = names
+            .iter()
+            .copied()
+            .filter(|name| self.mapping[name].contents().is_empty())
+            .collect();
+
+        let cycles = self.strongly_connected_components(&names);
+        let longest_chain = if cycles.is_empty() {
+            Some(self.find_longest_chain(&names))
+        } else {
+            None
+        };
+
+        Statistics {
+            longest_chain,
+            fan_in,
+            fan_out,
+            roots,
+            leaves,
+            cycles,
+        }
+    }
+
+    fn distinct_contents(&self, name: &str) -> usize {
+        self.mapping[name]
+            .contents()
+            .iter()
+            .map(|(_, contains)| *contains)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn find_longest_chain(&self, names: &[&'a str]) -> Box<[&'a str]> {
+        let mut memo = HashMap::new();
+        let mut best: Vec<&'a str> = Vec::new();
+        for name in names {
+            let chain = self.longest_chain_from(name, &mut memo);
+            if chain.len() > best.len() {
+                best = chain;
+            }
+        }
+        best.into_boxed_slice()
+    }
+
+    fn longest_chain_from(
+        &self,
+        name: &'a str,
+        memo: &mut HashMap<&'a str, Vec<&'a str>>,
+    ) -> Vec<&'a str> {
+        if let Some(chain) = memo.get(name) {
+            return chain.clone();
+        }
+        let mut contents = self.mapping[name]
+            .contents()
+            .iter()
+            .map(|(_, contains)| *contains)
+            .collect::<Vec<_>>();
+        contents.sort_unstable();
+
+        let mut longest: Vec<&'a str> = Vec::new();
+        for contains in contents {
+            let chain = self.longest_chain_from(contains, memo);
+            if chain.len() > longest.len() {
+                longest = chain;
+            }
+        }
+        longest.insert(0, name);
+        memo.insert(name, longest.clone());
+        longest
+    }
+
+    // only components that actually form a cycle are reported, i.e. more than one bag or a bag
+    // containing itself.
+    fn strongly_connected_components(&self, names: &[&'a str]) -> Box<[Box<[&'a str]>]> {
+        let mut tarjan = Tarjan {
+            index: 0,
+            indices: HashMap::new(),
+            low_links: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        };
+        for name in names {
+            if !tarjan.indices.contains_key(name) {
+                self.strong_connect(name, &mut tarjan);
+            }
+        }
+        tarjan
+            .components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || self.mapping[component[0]]
+                        .contents()
+                        .iter()
+                        .any(|(_, contains)| *contains == component[0])
+            })
+            .collect()
+    }
+
+    fn strong_connect(&self, name: &'a str, tarjan: &mut Tarjan<'a>) {
+        tarjan.indices.insert(name, tarjan.index);
+        tarjan.low_links.insert(name, tarjan.index);
+        tarjan.index += 1;
+        tarjan.stack.push(name);
+        tarjan.on_stack.insert(name);
+
+        for (_, contains) in self.mapping[name].contents() {
+            if !tarjan.indices.contains_key(contains) {
+                self.strong_connect(contains, tarjan);
+                let low_link = tarjan.low_links[name].min(tarjan.low_links[contains]);
+                tarjan.low_links.insert(name, low_link);
+            } else if tarjan.on_stack.contains(contains) {
+                let low_link = tarjan.low_links[name].min(tarjan.indices[contains]);
+                tarjan.low_links.insert(name, low_link);
+            }
+        }
+
+        if tarjan.low_links[name] == tarjan.indices[name] {
+            let mut component = Vec::new();
+            while let Some(member) = tarjan.stack.pop() {
+                tarjan.on_stack.remove(member);
+                component.push(member);
+                if member == name {
+                    break;
+                }
+            }
+            component.sort_unstable();
+            tarjan.components.push(component.into_boxed_slice());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::convert::TryFrom};
+
+    const INPUT: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.\ndark orange bags contain 3 bright white bags, 4 muted yellow bags.\nbright white bags contain 1 shiny gold bag.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.\ndark olive bags contain 3 faded blue bags, 4 dotted black bags.\nvibrant plum bags contain 5 faded blue bags, 6 dotted black bags.\nfaded blue bags contain no other bags.\ndotted black bags contain no other bags.";
+
+    #[test]
+    fn test_depth_and_longest_chain() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let statistics = registry.statistics();
+        assert_eq!(statistics.max_depth(), Some(4));
+        assert_eq!(
+            statistics.longest_chain().unwrap(),
+            [
+                "dark orange",
+                "bright white",
+                "shiny gold",
+                "dark olive",
+                "dotted black"
+            ]
+        );
+        assert!(statistics.cycles().is_empty());
+    }
+
+    #[test]
+    fn test_roots_leaves_and_fan() {
+        let registry = BagRegistry::try_from(INPUT).unwrap();
+        let statistics = registry.statistics();
+        assert_eq!(statistics.roots(), ["dark orange", "light red"]);
+        assert_eq!(statistics.leaves(), ["dotted black", "faded blue"]);
+        assert_eq!(statistics.fan_in()[&0], 2);
+        assert_eq!(statistics.fan_in()[&1], 2);
+        assert_eq!(statistics.fan_in()[&2], 4);
+        assert_eq!(statistics.fan_in()[&3], 1);
+        assert_eq!(statistics.fan_out()[&0], 2);
+        assert_eq!(statistics.fan_out()[&2], 6);
+        assert_eq!(statistics.fan_out()[&1], 1);
+    }
+
+    #[test]
+    fn test_cycles() {
+        let registry = BagRegistry::try_from("light red bags contain 1 shiny gold bag.\nshiny gold bags contain 1 dark red bag.\ndark red bags contain 2 shiny gold bags, 1 faded blue bag.\nfaded blue bags contain 1 faded blue bag.").unwrap();
+        let statistics = registry.statistics();
+        assert_eq!(statistics.max_depth(), None);
+        assert_eq!(statistics.cycles().len(), 2);
+        assert!(statistics
+            .cycles()
+            .iter()
+            .any(|cycle| **cycle == ["dark red", "shiny gold"]));
+        assert!(statistics
+            .cycles()
+            .iter()
+            .any(|cycle| **cycle == ["faded blue"]));
+    }
+}
@@ -0,0 +1,181 @@
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io::{Error, ErrorKind, Result},
+    ops::{Add, Mul},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Properties {
+    weight: f64, // kilograms
+    volume: f64, // litres
+}
+
+impl Properties {
+    pub fn new(weight: f64, volume: f64) -> Result<Self> {
+        Ok(Self {
+            weight: check_measure("weight", weight)?,
+            volume: check_measure("volume", volume)?,
+        })
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+}
+
+impl Add for Properties {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            weight: self.weight + other.weight,
+            volume: self.volume + other.volume,
+        }
+    }
+}
+
+impl Mul<u32> for Properties {
+    type Output = Self;
+
+    fn mul(self, count: u32) -> Self {
+        Self {
+            weight: self.weight * count as f64,
+            volume: self.volume * count as f64,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PropertyTable<'a> {
+    mapping: HashMap<&'a str, Properties>,
+}
+
+impl<'a> PropertyTable<'a> {
+    pub fn insert(&mut self, name: &'a str, properties: Properties) -> Result<()> {
+        check_measure("weight", properties.weight)?;
+        check_measure("volume", properties.volume)?;
+        self.mapping.insert(name, properties);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<Properties> {
+        self.mapping.get(name).copied().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Missing properties for bag: {}", name),
+            )
+        })
+    }
+}
+
+// NaN would pass every capacity comparison, so only finite, non-negative values are accepted.
+fn check_measure(name: &str, measure: f64) -> Result<f64> {
+    if measure.is_finite() && measure >= 0.0 {
+        Ok(measure)
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "The {} should be a finite, non-negative number: {}",
+                name, measure
+            ),
+        ))
+    }
+}
+
+fn parse_measure(value: &str) -> Result<f64> {
+    value
+        .parse::<f64>()
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
+
+// each line is of the form <name>,<weight>,<volume>
+impl<'a> TryFrom<&'a str> for PropertyTable<'a> {
+    type Error = Error;
+
+    fn try_from(string: &'a str) -> Result<Self> {
+        let mut table = Self::default();
+        for line in string.trim().lines() {
+            let mut fields = line.split(',').map(|field| field.trim());
+            match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some(name), Some(weight), Some(volume), None) => {
+                    let properties =
+                        Properties::new(parse_measure(weight)?, parse_measure(volume)?)?;
+                    table.insert(name, properties)?;
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Line should be of the form <name>,<weight>,<volume>: {}",
+                            line
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table() {
+        let table = PropertyTable::try_from("shiny gold, 1.5, 2\nfaded blue,0.25,0.5").unwrap();
+        assert_eq!(
+            table.get("shiny gold").unwrap(),
+            Properties::new(1.5, 2.0).unwrap()
+        );
+        assert_eq!(table.get("faded blue").unwrap().volume(), 0.5);
+        assert!(table.get("dotted black").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_table() {
+        assert!(PropertyTable::try_from("shiny gold, 1.5").is_err());
+        assert!(PropertyTable::try_from("shiny gold, heavy, 2").is_err());
+    }
+
+    #[test]
+    fn test_parse_non_finite_or_negative() {
+        let error = |table: &str| PropertyTable::try_from(table).unwrap_err().to_string();
+        assert_eq!(
+            error("shiny gold, NaN, 2"),
+            "The weight should be a finite, non-negative number: NaN"
+        );
+        assert_eq!(
+            error("shiny gold, -1, 2"),
+            "The weight should be a finite, non-negative number: -1"
+        );
+        assert_eq!(
+            error("shiny gold, inf, 2"),
+            "The weight should be a finite, non-negative number: inf"
+        );
+        assert_eq!(
+            error("shiny gold, 1, -0.5"),
+            "The volume should be a finite, non-negative number: -0.5"
+        );
+        assert!(PropertyTable::try_from("shiny gold, 0, 0").is_ok());
+    }
+
+    #[test]
+    fn test_constructors_reject_non_finite_or_negative() {
+        assert!(Properties::new(f64::NAN, 1.0).is_err());
+        assert!(Properties::new(1.0, f64::INFINITY).is_err());
+        assert!(Properties::new(-1.0, 1.0).is_err());
+        assert!(Properties::new(0.0, 0.0).is_ok());
+        let mut table = PropertyTable::default();
+        // arithmetic on valid properties can still overflow to infinity.
+        let infinite = Properties::new(f64::MAX, 1.0).unwrap() * 2;
+        assert!(table.insert("shiny gold", infinite).is_err());
+        assert!(table.get("shiny gold").is_err());
+    }
+}
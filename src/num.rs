@@ -0,0 +1,3 @@
+pub mod big_uint;
+pub mod count;
+pub mod random;
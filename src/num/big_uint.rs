@@ -0,0 +1,184 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter, Result},
+    ops::{Add, Mul},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigUint {
+    limbs: Vec<u32>, // little endian base 2^32 digits, with no trailing zero limbs.
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn normalize(mut self) -> Self {
+        while let Some(0) = self.limbs.last() {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    fn div_rem_small(&self, divisor: u32) -> (Self, u32) {
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (index, limb) in self.limbs.iter().enumerate().rev() {
+            let current = (remainder << 32) | *limb as u64;
+            quotient[index] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        (Self { limbs: quotient }.normalize(), remainder as u32)
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        Self { limbs: vec![value] }.normalize()
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        }
+        .normalize()
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        Self {
+            limbs: (0..4).map(|index| (value >> (32 * index)) as u32).collect(),
+        }
+        .normalize()
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        let mut carry = 0u64;
+        for index in 0..self.limbs.len().max(other.limbs.len()) {
+            let sum = *self.limbs.get(index).unwrap_or(&0) as u64
+                + *other.limbs.get(index).unwrap_or(&0) as u64
+                + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint { limbs }.normalize()
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: BigUint) -> BigUint {
+        &self + &other
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let product = *a as u64 * *b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint { limbs }.normalize()
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        &self * &other
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // peel off groups of 9 decimal digits, least significant first.
+        let mut groups = Vec::new();
+        let mut current = self.clone();
+        while !current.is_zero() {
+            let (quotient, remainder) = current.div_rem_small(1_000_000_000);
+            groups.push(remainder);
+            current = quotient;
+        }
+
+        let mut groups = groups.iter().rev();
+        write!(f, "{}", groups.next().unwrap())?;
+        for group in groups {
+            write!(f, "{:09}", group)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", BigUint::zero()), "0");
+        assert_eq!(format!("{}", BigUint::from(1_000_000_007u32)), "1000000007");
+        assert_eq!(
+            format!("{}", BigUint::from(u128::MAX)),
+            "340282366920938463463374607431768211455"
+        );
+    }
+
+    #[test]
+    fn test_add_with_carry() {
+        let sum = BigUint::from(u64::MAX) + BigUint::from(1u32);
+        assert_eq!(sum, BigUint::from(1u128 << 64));
+    }
+
+    #[test]
+    fn test_mul_beyond_u128() {
+        let value = BigUint::from(u128::MAX) * BigUint::from(u128::MAX);
+        assert_eq!(
+            format!("{}", value),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert!(value > BigUint::from(u128::MAX));
+    }
+}
@@ -0,0 +1,52 @@
+use crate::num::big_uint::BigUint;
+
+pub trait Count: Clone {
+    fn zero() -> Self;
+    fn from_u32(value: u32) -> Self;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_count {
+    ($($ty:ty),+) => {
+        $(
+            impl Count for $ty {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn from_u32(value: u32) -> Self {
+                    value as $ty
+                }
+
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$ty>::checked_add(*self, *other)
+                }
+
+                fn checked_mul(&self, other: &Self) -> Option<Self> {
+                    <$ty>::checked_mul(*self, *other)
+                }
+            }
+        )+
+    };
+}
+
+impl_count!(u32, u64, u128);
+
+impl Count for BigUint {
+    fn zero() -> Self {
+        BigUint::zero()
+    }
+
+    fn from_u32(value: u32) -> Self {
+        BigUint::from(value)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+}
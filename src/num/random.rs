@@ -0,0 +1,84 @@
+use std::ops::RangeInclusive;
+
+// a small seeded xorshift64* generator, the same seed always gives the same sequence. not suitable
+// for anything security related.
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on zero, and nearby seeds should still give unrelated sequences.
+        let mut random = Self {
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        };
+        if random.state == 0 {
+            random.state = 1;
+        }
+        random.next_u64();
+        random
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // the bias from the modulo is negligible for the small bounds used here.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "bound must be positive");
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn in_range(&mut self, range: RangeInclusive<u32>) -> u32 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "range must not be empty");
+        start + (self.next_u64() % (u64::from(end - start) + 1)) as u32
+    }
+
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded() {
+        let sequence = |seed| {
+            let mut random = Random::new(seed);
+            (0..8).map(|_| random.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(42), sequence(42));
+        assert_ne!(sequence(42), sequence(43));
+        assert_ne!(sequence(0), vec![0; 8]);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut random = Random::new(7);
+        let mut seen = [false; 5];
+        for _ in 0..1000 {
+            let value = random.in_range(10..=14);
+            assert!((10..=14).contains(&value));
+            seen[(value - 10) as usize] = true;
+        }
+        assert!(seen.iter().all(|seen| *seen));
+        assert_eq!(random.in_range(3..=3), 3);
+    }
+}
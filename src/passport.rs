@@ -1,9 +1,25 @@
-use std::{
-    io::{Error, ErrorKind, Result},
-    str::FromStr,
+pub mod fields;
+pub mod generator;
+pub mod model;
+pub mod policy;
+pub mod reader;
+pub mod record;
+pub mod repair;
+pub mod schema;
+pub mod serialize;
+pub mod validation;
+
+use {
+    policy::{DuplicatePolicy, FieldPolicy, UnknownPolicy},
+    std::{
+        io::{Error, ErrorKind, Result},
+        mem,
+        str::FromStr,
+    },
+    validation::FieldName,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct PassportValidation {
     byr: Option<bool>,
     iyr: Option<bool>,
@@ -12,6 +28,9 @@ pub struct PassportValidation {
     hcl: Option<bool>,
     ecl: Option<bool>,
     pid: Option<bool>,
+    cid: Option<bool>,
+    warnings: Vec<FieldName>,
+    extras: Vec<(String, String)>,
 }
 
 impl PassportValidation {
@@ -43,6 +62,104 @@ impl PassportValidation {
         if self.pid.is_none() {
             self.pid = other.pid;
         }
+
+        if self.cid.is_none() {
+            self.cid = other.cid;
+        }
+
+        self.warnings.extend(other.warnings);
+        self.extras.extend(other.extras);
+    }
+
+    // a field for each value discarded under DuplicatePolicy::Warn.
+    pub fn warnings(&self) -> &[FieldName] {
+        &self.warnings
+    }
+
+    // unknown fields, only kept when parsed with UnknownPolicy::KeepAsExtra.
+    pub fn extras(&self) -> &[(String, String)] {
+        &self.extras
+    }
+
+    fn slot(&mut self, field: FieldName) -> &mut Option<bool> {
+        match field {
+            FieldName::Byr => &mut self.byr,
+            FieldName::Iyr => &mut self.iyr,
+            FieldName::Eyr => &mut self.eyr,
+            FieldName::Hgt => &mut self.hgt,
+            FieldName::Hcl => &mut self.hcl,
+            FieldName::Ecl => &mut self.ecl,
+            FieldName::Pid => &mut self.pid,
+            FieldName::Cid => &mut self.cid,
+        }
+    }
+
+    // sets a field that may already be set, following the policy.
+    fn set(&mut self, field: FieldName, valid: bool, policy: DuplicatePolicy) -> Result<()> {
+        let slot = self.slot(field);
+        match (slot.is_some(), policy) {
+            (false, _) | (true, DuplicatePolicy::KeepLast) => *slot = Some(valid),
+            (true, DuplicatePolicy::Reject) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Duplicate field: {}", field.key()),
+                ))
+            }
+            (true, DuplicatePolicy::Warn) => self.warnings.push(field),
+            (true, DuplicatePolicy::KeepFirst) => {}
+        }
+        Ok(())
+    }
+
+    // merge applies the default policy, where the first value wins.
+    pub fn merge_with_policy(&mut self, mut other: Self, policy: FieldPolicy) -> Result<()> {
+        self.warnings.append(&mut other.warnings);
+        self.extras.append(&mut other.extras);
+        for field in FieldName::ALL.iter() {
+            if let Some(valid) = mem::take(other.slot(*field)) {
+                self.set(*field, valid, policy.duplicates)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn parse_with_policy(string: &str, policy: FieldPolicy) -> Result<Self> {
+        let mut validation = Self::default();
+        for component in string.trim().split(' ') {
+            let component = component.trim();
+            let mut subcomponents = component.split(':');
+            let key = subcomponents.next().unwrap();
+            let value = subcomponents
+                .next()
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Invalid data"))?;
+            let field = match key.parse::<FieldName>() {
+                Ok(field) => field,
+                Err(_) => {
+                    match policy.unknown {
+                        UnknownPolicy::Reject => {
+                            return Err(Error::new(ErrorKind::InvalidData, "Invalid data"))
+                        }
+                        UnknownPolicy::Ignore => {}
+                        UnknownPolicy::KeepAsExtra => {
+                            validation.extras.push((key.to_string(), value.to_string()))
+                        }
+                    }
+                    continue;
+                }
+            };
+            let valid = match field {
+                FieldName::Byr => validate_byr(value),
+                FieldName::Iyr => validate_iyr(value),
+                FieldName::Eyr => validate_eyr(value),
+                FieldName::Hgt => validate_hgt(value),
+                FieldName::Hcl => validate_hcl(value),
+                FieldName::Ecl => validate_ecl(value),
+                FieldName::Pid => validate_pid(value),
+                FieldName::Cid => true, // optional, and any value will do.
+            };
+            validation.set(field, valid, policy.duplicates)?;
+        }
+        Ok(validation)
     }
 
     pub fn valid(&self) -> bool {
@@ -67,80 +184,11 @@ impl PassportValidation {
     }
 }
 
-impl Default for PassportValidation {
-    fn default() -> Self {
-        Self {
-            byr: None,
-            iyr: None,
-            eyr: None,
-            hgt: None,
-            hcl: None,
-            ecl: None,
-            pid: None,
-        }
-    }
-}
-
 impl FromStr for PassportValidation {
     type Err = Error;
 
     fn from_str(string: &str) -> Result<Self> {
-        let mut byr = None;
-        let mut iyr = None;
-        let mut eyr = None;
-        let mut hgt = None;
-        let mut hcl = None;
-        let mut ecl = None;
-        let mut pid = None;
-
-        for component in string.trim().split(" ") {
-            let component = component.trim();
-            let mut subcomponents = component.split(":");
-            let key = subcomponents.next();
-            if let Some(value) = subcomponents.next() {
-                match key {
-                    Some("byr") => {
-                        byr = Some(validate_byr(value));
-                    }
-                    Some("iyr") => {
-                        iyr = Some(validate_iyr(value));
-                    }
-                    Some("eyr") => {
-                        eyr = Some(validate_eyr(value));
-                    }
-                    Some("hgt") => {
-                        hgt = Some(validate_hgt(value));
-                    }
-                    Some("hcl") => {
-                        hcl = Some(validate_hcl(value));
-                    }
-                    Some("ecl") => {
-                        ecl = Some(validate_ecl(value));
-                    }
-                    Some("pid") => {
-                        pid = Some(validate_pid(value));
-                    }
-                    Some("cid") => {
-                        // ignored
-                    }
-                    _ => {
-                        return Err(Error::new(ErrorKind::InvalidData, "Invalid data"));
-                    }
-                }
-            } else {
-                return Err(Error::new(ErrorKind::InvalidData, "Invalid data"));
-            }
-        }
-
-        Ok(Self {
-            byr,
-            iyr,
-            eyr,
-            hgt,
-            hcl,
-            ecl,
-            pid,
-        })
+        Self::parse_with_policy(string, FieldPolicy::default())
     }
 }
 
@@ -181,14 +229,14 @@ fn validate_eyr(input: &str) -> bool {
 }
 
 fn validate_hgt(input: &str) -> bool {
-    if let Some(index) = input.find("cm") {
-        if let Ok(value) = &input[..index].parse::<u8>() {
+    if let Some(number) = input.strip_suffix("cm") {
+        if let Ok(value) = &number.parse::<u8>() {
             *value >= 150 && *value <= 193
         } else {
             false
         }
-    } else if let Some(index) = input.find("in") {
-        if let Ok(value) = &input[..index].parse::<u8>() {
+    } else if let Some(number) = input.strip_suffix("in") {
+        if let Ok(value) = &number.parse::<u8>() {
             *value >= 59 && *value <= 76
         } else {
             false
@@ -199,7 +247,7 @@ fn validate_hgt(input: &str) -> bool {
 }
 
 fn validate_hcl(input: &str) -> bool {
-    if input.starts_with("#") {
+    if input.starts_with("#") && input.len() == 7 {
         input
             .chars()
             .skip(1)
@@ -216,20 +264,19 @@ fn validate_ecl(input: &str) -> bool {
 }
 
 fn validate_pid(input: &str) -> bool {
-    if input.len() == 9 {
-        if let Ok(_) = input.parse::<u64>() {
-            true
-        } else {
-            false
-        }
-    } else {
-        false
-    }
+    input.len() == 9 && input.chars().all(|c| c.is_ascii_digit())
 }
 
 #[cfg(test)]
 mod tests {
-    use {super::PassportValidation, std::io::Result};
+    use {
+        super::{
+            policy::{DuplicatePolicy, FieldPolicy, UnknownPolicy},
+            validation::FieldName,
+            PassportValidation,
+        },
+        std::io::Result,
+    };
 
     #[test]
     fn valid_full_2_lines() -> Result<()> {
@@ -274,4 +321,65 @@ mod tests {
         assert!(!passport_validation.valid());
         Ok(())
     }
+
+    #[test]
+    fn policies_apply_to_lines_and_merges() -> Result<()> {
+        let policy = |duplicates, unknown| FieldPolicy {
+            duplicates,
+            unknown,
+        };
+        let keep_first = FieldPolicy::default();
+        let keep_last = policy(DuplicatePolicy::KeepLast, UnknownPolicy::Reject);
+        let reject = policy(DuplicatePolicy::Reject, UnknownPolicy::Ignore);
+
+        let line = "byr:1937 byr:1900";
+        assert_eq!(
+            PassportValidation::parse_with_policy(line, keep_first)?.byr,
+            Some(true)
+        );
+        assert_eq!(
+            PassportValidation::parse_with_policy(line, keep_last)?.byr,
+            Some(false)
+        );
+        assert!(PassportValidation::parse_with_policy(line, reject).is_err());
+
+        assert!("byr:1937 zzz:1".parse::<PassportValidation>().is_err());
+        assert!(PassportValidation::parse_with_policy("byr:1937 zzz:1", reject).is_ok());
+
+        let mut validation = "byr:1937".parse::<PassportValidation>()?;
+        validation.merge_with_policy("byr:1900".parse()?, keep_last)?;
+        assert_eq!(validation.byr, Some(false));
+        assert!(validation
+            .merge_with_policy("byr:1937".parse()?, reject)
+            .is_err());
+
+        let cid = "cid:1 cid:2";
+        assert!(PassportValidation::parse_with_policy(cid, reject).is_err());
+        assert!(cid.parse::<PassportValidation>().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn warnings_and_extras_are_kept() -> Result<()> {
+        let policy = FieldPolicy {
+            duplicates: DuplicatePolicy::Warn,
+            unknown: UnknownPolicy::KeepAsExtra,
+        };
+        let mut validation =
+            PassportValidation::parse_with_policy("byr:1937 zzz:1 byr:1900", policy)?;
+        assert_eq!(validation.byr, Some(true));
+        assert_eq!(validation.warnings(), [FieldName::Byr]);
+        assert_eq!(validation.extras(), [("zzz".to_string(), "1".to_string())]);
+
+        let line = PassportValidation::parse_with_policy("cid:1 yyy:2", policy)?;
+        validation.merge_with_policy(line, policy)?;
+        validation.merge_with_policy("cid:2 byr:2000".parse()?, policy)?;
+        assert_eq!(validation.byr, Some(true));
+        assert_eq!(
+            validation.warnings(),
+            [FieldName::Byr, FieldName::Byr, FieldName::Cid]
+        );
+        assert_eq!(validation.extras().len(), 2);
+        Ok(())
+    }
 }
@@ -0,0 +1,272 @@
+use {
+    crate::passport::validation::{
+        EyeColourError, HairColourError, HeightError, PidError, YearError,
+    },
+    std::{
+        convert::TryFrom,
+        fmt::{self, Display, Formatter},
+        str::FromStr,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Height {
+    Cm(u16),
+    In(u16),
+}
+
+impl Height {
+    pub fn validate(&self) -> Result<(), HeightError> {
+        let (value, min, max, unit) = match *self {
+            Height::Cm(value) => (value, 150, 193, "cm"),
+            Height::In(value) => (value, 59, 76, "in"),
+        };
+        if (min..=max).contains(&value) {
+            Ok(())
+        } else {
+            Err(HeightError::OutOfRange {
+                value,
+                min,
+                max,
+                unit,
+            })
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    // conversions round to the nearest whole unit, using 1in = 2.54cm.
+    pub fn to_centimetres(&self) -> Height {
+        match *self {
+            Height::Cm(value) => Height::Cm(value),
+            Height::In(value) => {
+                let value = (u32::from(value) * 254 + 50) / 100;
+                Height::Cm(u16::try_from(value).unwrap_or(u16::MAX))
+            }
+        }
+    }
+
+    pub fn to_inches(&self) -> Height {
+        match *self {
+            Height::Cm(value) => Height::In(((u32::from(value) * 100 + 127) / 254) as u16),
+            Height::In(value) => Height::In(value),
+        }
+    }
+}
+
+impl FromStr for Height {
+    type Err = HeightError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let (number, constructor): (&str, fn(u16) -> Height) =
+            if let Some(number) = string.strip_suffix("cm") {
+                (number, Height::Cm)
+            } else if let Some(number) = string.strip_suffix("in") {
+                (number, Height::In)
+            } else {
+                return Err(HeightError::MissingUnit);
+            };
+        number
+            .parse()
+            .map(constructor)
+            .map_err(|_| HeightError::InvalidNumber(number.to_string()))
+    }
+}
+
+impl Display for Height {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Height::Cm(value) => write!(f, "{}cm", value),
+            Height::In(value) => write!(f, "{}in", value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl FromStr for Rgb {
+    type Err = HairColourError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let digits = string
+            .strip_prefix('#')
+            .ok_or(HairColourError::MissingHash)?;
+        // lowercase only, as required by the puzzle rules and the other validators.
+        if let Some(c) = digits.chars().find(|c| !matches!(c, '0'..='9' | 'a'..='f')) {
+            return Err(HairColourError::BadHexDigit(c));
+        }
+        if digits.len() != 6 {
+            return Err(HairColourError::WrongLength(digits.len()));
+        }
+        let component = |index: usize| u8::from_str_radix(&digits[index..index + 2], 16).unwrap();
+        Ok(Self {
+            r: component(0),
+            g: component(2),
+            b: component(4),
+        })
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EyeColour {
+    Amber,
+    Blue,
+    Brown,
+    Grey,
+    Green,
+    Hazel,
+    Other,
+}
+
+impl EyeColour {
+    pub const ALL: [EyeColour; 7] = [
+        EyeColour::Amber,
+        EyeColour::Blue,
+        EyeColour::Brown,
+        EyeColour::Grey,
+        EyeColour::Green,
+        EyeColour::Hazel,
+        EyeColour::Other,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            EyeColour::Amber => "amb",
+            EyeColour::Blue => "blu",
+            EyeColour::Brown => "brn",
+            EyeColour::Grey => "gry",
+            EyeColour::Green => "grn",
+            EyeColour::Hazel => "hzl",
+            EyeColour::Other => "oth",
+        }
+    }
+}
+
+impl FromStr for EyeColour {
+    type Err = EyeColourError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        EyeColour::ALL
+            .iter()
+            .find(|colour| colour.code() == string)
+            .copied()
+            .ok_or_else(|| EyeColourError::Unknown(string.to_string()))
+    }
+}
+
+impl Display for EyeColour {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pid(String);
+
+impl Pid {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Pid {
+    type Err = PidError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        if let Some(c) = string.chars().find(|c| !c.is_ascii_digit()) {
+            Err(PidError::NotADigit(c))
+        } else if string.len() != 9 {
+            Err(PidError::WrongLength(string.len()))
+        } else {
+            Ok(Self(string.to_string()))
+        }
+    }
+}
+
+impl Display for Pid {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub fn parse_year(string: &str) -> Result<u16, YearError> {
+    if string.len() == 4 && string.chars().all(|c| c.is_ascii_digit()) {
+        Ok(string.parse().unwrap())
+    } else {
+        Err(YearError::NotFourDigits(string.to_string()))
+    }
+}
+
+pub fn validate_year(value: u16, min: u16, max: u16) -> Result<(), YearError> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(YearError::OutOfRange { value, min, max })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_height() {
+        assert_eq!("183cm".parse::<Height>().unwrap(), Height::Cm(183));
+        assert_eq!("59in".parse::<Height>().unwrap(), Height::In(59));
+        assert_eq!("190".parse::<Height>(), Err(HeightError::MissingUnit));
+        assert!(Height::Cm(150).is_valid());
+        assert!(!Height::In(77).is_valid());
+        assert_eq!(Height::In(59).to_centimetres(), Height::Cm(150));
+        assert_eq!(Height::Cm(193).to_inches(), Height::In(76));
+        assert_eq!(Height::Cm(183).to_centimetres(), Height::Cm(183));
+    }
+
+    #[test]
+    fn test_rgb() {
+        let colour = "#7d3b0c".parse::<Rgb>().unwrap();
+        assert_eq!(
+            colour,
+            Rgb {
+                r: 125,
+                g: 59,
+                b: 12
+            }
+        );
+        assert_eq!(format!("{}", colour), "#7d3b0c");
+        assert_eq!("123abc".parse::<Rgb>(), Err(HairColourError::MissingHash));
+        assert_eq!(
+            "#123abz".parse::<Rgb>(),
+            Err(HairColourError::BadHexDigit('z'))
+        );
+        assert_eq!(
+            "#123abcd".parse::<Rgb>(),
+            Err(HairColourError::WrongLength(7))
+        );
+        assert_eq!(
+            "#7D3B0C".parse::<Rgb>(),
+            Err(HairColourError::BadHexDigit('D'))
+        );
+    }
+
+    #[test]
+    fn test_eye_colour_and_pid() {
+        assert_eq!("hzl".parse::<EyeColour>().unwrap(), EyeColour::Hazel);
+        assert!("wat".parse::<EyeColour>().is_err());
+        assert_eq!("000000001".parse::<Pid>().unwrap().as_str(), "000000001");
+        assert_eq!("0123456789".parse::<Pid>(), Err(PidError::WrongLength(10)));
+        assert!(parse_year("02002").is_err());
+    }
+}
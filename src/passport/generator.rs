@@ -0,0 +1,309 @@
+use crate::{num::random::Random, passport::validation::FieldName};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldMode {
+    Valid,
+    Missing,
+    Invalid, // present, but out of range or malformed. cid has no rules so is always valid.
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratedPassport {
+    text: String,
+    expected_valid: bool,
+}
+
+impl GeneratedPassport {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    // whether the record should pass the full rules, with cid optional.
+    pub fn expected_valid(&self) -> bool {
+        self.expected_valid
+    }
+}
+
+const HEX_DIGITS: &[u8] = b"0123456789abcdef";
+
+// produces random passport records, seeded so that a failing record can be reproduced. valid
+// values favour the boundaries of each rule, as that's where validators usually go wrong.
+pub struct Generator {
+    random: Random,
+    modes: [FieldMode; 8],
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            random: Random::new(seed),
+            modes: [FieldMode::Valid; 8],
+        }
+    }
+
+    pub fn set_mode(&mut self, field: FieldName, mode: FieldMode) {
+        self.modes[field as usize] = mode;
+    }
+
+    pub fn mode(&self, field: FieldName) -> FieldMode {
+        self.modes[field as usize]
+    }
+
+    // picks a new mode for every field, mostly valid so that valid records aren't too rare.
+    pub fn randomise_modes(&mut self) {
+        for field in FieldName::ALL.iter() {
+            let mode = match self.random.below(6) {
+                0 => FieldMode::Missing,
+                1 => FieldMode::Invalid,
+                _ => FieldMode::Valid,
+            };
+            self.set_mode(*field, mode);
+        }
+    }
+
+    pub fn generate(&mut self) -> GeneratedPassport {
+        let mut components = Vec::new();
+        let mut expected_valid = true;
+        for field in FieldName::ALL.iter() {
+            let mode = self.mode(*field);
+            if *field != FieldName::Cid && mode != FieldMode::Valid {
+                expected_valid = false;
+            }
+            if mode != FieldMode::Missing {
+                let value = self.value(*field, mode == FieldMode::Valid);
+                components.push(format!("{}:{}", field.key(), value));
+            }
+        }
+
+        self.random.shuffle(&mut components);
+        let mut text = String::new();
+        for (index, component) in components.iter().enumerate() {
+            if index > 0 {
+                text.push(if self.random.chance(1, 4) { '\n' } else { ' ' });
+            }
+            text.push_str(component);
+        }
+        GeneratedPassport {
+            text,
+            expected_valid,
+        }
+    }
+
+    fn value(&mut self, field: FieldName, valid: bool) -> String {
+        match (field, valid) {
+            (FieldName::Byr, true) => self.number_in(1920, 2002).to_string(),
+            (FieldName::Iyr, true) => self.number_in(2010, 2020).to_string(),
+            (FieldName::Eyr, true) => self.number_in(2020, 2030).to_string(),
+            (FieldName::Byr, false) => self.invalid_year(1920, 2002),
+            (FieldName::Iyr, false) => self.invalid_year(2010, 2020),
+            (FieldName::Eyr, false) => self.invalid_year(2020, 2030),
+            (FieldName::Hgt, true) => {
+                if self.random.chance(1, 2) {
+                    format!("{}cm", self.number_in(150, 193))
+                } else {
+                    format!("{}in", self.number_in(59, 76))
+                }
+            }
+            (FieldName::Hgt, false) => match self.random.below(6) {
+                0 => format!("{}cm", self.outside(150, 193)),
+                1 => format!("{}in", self.outside(59, 76)),
+                2 => self.number_in(150, 193).to_string(), // no unit.
+                3 => format!("{}mm", self.number_in(150, 193)),
+                4 => format!("{}cmcm", self.number_in(150, 193)),
+                _ => format!("{}in", self.number_in(150, 193)), // cm value with inches.
+            },
+            (FieldName::Hcl, true) => format!("#{}", self.hex(6)),
+            (FieldName::Hcl, false) => match self.random.below(7) {
+                0 => format!("#{}", self.hex(7)),
+                1 => format!("#{}", self.hex(5)),
+                2 => self.hex(6), // no hash.
+                3 => format!("#{}{}", self.hex(5), self.not_hex()),
+                4 => format!("#{}", self.mixed_case_hex().to_ascii_uppercase()),
+                5 => format!("#{}", self.mixed_case_hex()),
+                _ => format!("#{}{}", self.hex(6), self.not_hex()),
+            },
+            (FieldName::Ecl, true) => {
+                let codes = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+                self.random.choose(&codes).to_string()
+            }
+            (FieldName::Ecl, false) => {
+                let codes = ["xry", "amber", "gr", "AMB", "blue", "zzz"];
+                self.random.choose(&codes).to_string()
+            }
+            (FieldName::Pid, true) => {
+                // leading zeros are valid, and easily lost by parsing the ID as a number.
+                let zeros = if self.random.chance(1, 2) {
+                    self.random.below(9)
+                } else {
+                    0
+                };
+                format!("{}{}", "0".repeat(zeros), self.digits(9 - zeros))
+            }
+            (FieldName::Pid, false) => match self.random.below(4) {
+                0 => self.digits(8),
+                1 => self.digits(10),
+                2 => format!("+{}", self.digits(8)),
+                _ => format!("{}x{}", self.digits(4), self.digits(4)),
+            },
+            (FieldName::Cid, _) => self.random.in_range(100..=999).to_string(),
+        }
+    }
+
+    // a number in the range, picking either boundary a third of the time.
+    fn number_in(&mut self, min: u32, max: u32) -> u32 {
+        match self.random.below(6) {
+            0 => min,
+            1 => max,
+            _ => self.random.in_range(min..=max),
+        }
+    }
+
+    // a number just outside the range most of the time, occasionally far outside it.
+    fn outside(&mut self, min: u32, max: u32) -> u32 {
+        match self.random.below(4) {
+            0 => min - 1,
+            1 => max + 1,
+            2 => self.random.in_range(0..=min - 1),
+            _ => self.random.in_range(max + 1..=max * 2),
+        }
+    }
+
+    fn invalid_year(&mut self, min: u32, max: u32) -> String {
+        match self.random.below(4) {
+            0 => format!("0{}", self.random.in_range(min..=max)), // five digits.
+            1 => self.random.in_range(100..=999).to_string(),
+            2 => format!("{}x", self.random.in_range(100..=999)),
+            _ => format!("{:04}", self.outside(min, max).min(9999)),
+        }
+    }
+
+    fn hex(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| *self.random.choose(HEX_DIGITS) as char)
+            .collect()
+    }
+
+    // six hex digits with at least one uppercase letter, which the rules reject.
+    fn mixed_case_hex(&mut self) -> String {
+        let mut digits = self.hex(6).into_bytes();
+        let index = self.random.below(digits.len());
+        digits[index] = *self.random.choose(b"ABCDEF");
+        digits.into_iter().map(char::from).collect()
+    }
+
+    fn not_hex(&mut self) -> char {
+        *self.random.choose(b"ghijklmnopqrstuvwxyz") as char
+    }
+
+    fn digits(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| (b'0' + self.random.below(10) as u8) as char)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::passport::{model::Passport, schema::Schema, PassportValidation},
+        std::io::Result,
+    };
+
+    fn legacy_validation(text: &str) -> Result<PassportValidation> {
+        let mut validation = PassportValidation::default();
+        for line in text.lines() {
+            validation.merge(line.parse()?);
+        }
+        Ok(validation)
+    }
+
+    // every validator in the crate should agree with what the generator intended.
+    fn check(generated: &GeneratedPassport) -> Result<()> {
+        let text = generated.text();
+        let expected = generated.expected_valid();
+        assert_eq!(legacy_validation(text)?.valid_full(), expected, "{}", text);
+        let (passport, report) = Passport::parse_with_report(text);
+        assert_eq!(report.is_valid(), expected, "{}\n{}", text, report);
+        assert_eq!(
+            passport.is_valid() && report.is_valid(),
+            expected,
+            "{}",
+            text
+        );
+        let report = Schema::north_pole_credentials().validate(&text.parse()?);
+        assert_eq!(report.is_valid(), expected, "{}\n{}", text, report);
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_output() {
+        let mut first = Generator::new(2020);
+        let mut second = Generator::new(2020);
+        for _ in 0..10 {
+            first.randomise_modes();
+            second.randomise_modes();
+            assert_eq!(first.generate(), second.generate());
+        }
+
+        let mut generator = Generator::new(1);
+        generator.set_mode(FieldName::Pid, FieldMode::Missing);
+        generator.set_mode(FieldName::Cid, FieldMode::Missing);
+        let generated = generator.generate();
+        assert!(!generated.text().contains("pid:") && !generated.text().contains("cid:"));
+        assert!(!generated.expected_valid());
+    }
+
+    #[test]
+    fn test_validators_agree_with_the_rules() -> Result<()> {
+        let mut generator = Generator::new(4);
+        for _ in 0..2000 {
+            generator.randomise_modes();
+            check(&generator.generate())?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_each_invalid_field_is_rejected() -> Result<()> {
+        let mut generator = Generator::new(5);
+        for field in FieldName::ALL.iter() {
+            for mode in [FieldMode::Missing, FieldMode::Invalid].iter() {
+                for field in FieldName::ALL.iter() {
+                    generator.set_mode(*field, FieldMode::Valid);
+                }
+                generator.set_mode(*field, *mode);
+                for _ in 0..200 {
+                    check(&generator.generate())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_boundaries() -> Result<()> {
+        let base = "byr:1920 iyr:2020 eyr:2030 ecl:oth";
+        let cases = [
+            ("hgt:150cm hcl:#abcdef pid:000000001", true),
+            ("hgt:193cm hcl:#abcdef pid:000000001", true),
+            ("hgt:59in hcl:#abcdef pid:000000001", true),
+            ("hgt:76in hcl:#abcdef pid:000000001", true),
+            ("hgt:149cm hcl:#abcdef pid:000000001", false),
+            ("hgt:194cm hcl:#abcdef pid:000000001", false),
+            ("hgt:150cm hcl:#abcdef0 pid:000000001", false),
+            ("hgt:150cm hcl:#abcdefg pid:000000001", false),
+            ("hgt:150cm hcl:#ABCDEF pid:000000001", false),
+            ("hgt:150cm hcl:#abcDef pid:000000001", false),
+            ("hgt:150cm hcl:#abcdef pid:00000001", false),
+            ("hgt:150cm hcl:#abcdef pid:+00000001", false),
+        ];
+        for (fields, expected_valid) in cases.iter() {
+            check(&GeneratedPassport {
+                text: format!("{} {}", base, fields),
+                expected_valid: *expected_valid,
+            })?;
+        }
+        Ok(())
+    }
+}
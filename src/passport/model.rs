@@ -0,0 +1,434 @@
+use {
+    crate::passport::{
+        fields::{parse_year, validate_year, EyeColour, Height, Pid, Rgb},
+        policy::{Decision, DuplicatePolicy, FieldPolicy, UnknownPolicy},
+        validation::{FieldName, ValidationError, ValidationReport},
+    },
+    std::{
+        io::{Error, Result},
+        str::FromStr,
+    },
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Passport {
+    byr: Option<u16>,
+    iyr: Option<u16>,
+    eyr: Option<u16>,
+    hgt: Option<Height>,
+    // hcl, ecl and pid are kept as given, their rules are about the text itself.
+    hcl: Option<String>,
+    ecl: Option<String>,
+    pid: Option<String>,
+    cid: Option<String>,
+    extras: Vec<(String, String)>,
+}
+
+impl Passport {
+    pub fn byr(&self) -> Option<u16> {
+        self.byr
+    }
+
+    pub fn iyr(&self) -> Option<u16> {
+        self.iyr
+    }
+
+    pub fn eyr(&self) -> Option<u16> {
+        self.eyr
+    }
+
+    pub fn hgt(&self) -> Option<Height> {
+        self.hgt
+    }
+
+    pub fn hcl(&self) -> Option<&str> {
+        self.hcl.as_deref()
+    }
+
+    pub fn ecl(&self) -> Option<&str> {
+        self.ecl.as_deref()
+    }
+
+    pub fn pid(&self) -> Option<&str> {
+        self.pid.as_deref()
+    }
+
+    pub fn cid(&self) -> Option<&str> {
+        self.cid.as_deref()
+    }
+
+    // unknown fields, only kept when parsed with UnknownPolicy::KeepAsExtra.
+    pub fn extras(&self) -> &[(String, String)] {
+        &self.extras
+    }
+
+    pub fn has_required_fields(&self) -> bool {
+        self.byr.is_some()
+            && self.iyr.is_some()
+            && self.eyr.is_some()
+            && self.hgt.is_some()
+            && self.hcl.is_some()
+            && self.ecl.is_some()
+            && self.pid.is_some()
+    }
+
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        self.validate_into(&mut report);
+        report
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_valid()
+    }
+
+    // parses as much of the record as possible, reporting malformed values alongside the
+    // validation errors of the fields that could be parsed.
+    pub fn parse_with_report(string: &str) -> (Self, ValidationReport) {
+        Self::parse_with_policy(string, FieldPolicy::default())
+    }
+
+    // like parse_with_report, but with duplicate and unknown fields handled by the policy, the
+    // decisions it made are recorded in the report.
+    pub fn parse_with_policy(string: &str, policy: FieldPolicy) -> (Self, ValidationReport) {
+        let mut report = ValidationReport::default();
+        let passport = Self::parse_fields(string, policy, &mut report);
+        passport.validate_into(&mut report);
+        (passport, report)
+    }
+
+    // checks a single value against the rules for its field, as if it appeared in a record.
+    pub fn validate_field(
+        field: FieldName,
+        value: &str,
+    ) -> std::result::Result<(), ValidationError> {
+        let mut report = ValidationReport::default();
+        let component = format!("{}:{}", field.key(), value);
+        let passport = Self::parse_fields(&component, FieldPolicy::default(), &mut report);
+        passport.validate_into(&mut report);
+        match report
+            .errors()
+            .iter()
+            .find(|error| error.field() == Some(field))
+        {
+            Some(error) => Err(error.clone()),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_into(&self, report: &mut ValidationReport) {
+        let years = [
+            (FieldName::Byr, self.byr, 1920, 2002),
+            (FieldName::Iyr, self.iyr, 2010, 2020),
+            (FieldName::Eyr, self.eyr, 2020, 2030),
+        ];
+        for (field, year, min, max) in years.iter() {
+            if let Some(Err(error)) = year.map(|year| validate_year(year, *min, *max)) {
+                report.push(match field {
+                    FieldName::Byr => ValidationError::Byr(error),
+                    FieldName::Iyr => ValidationError::Iyr(error),
+                    _ => ValidationError::Eyr(error),
+                });
+            }
+        }
+        if let Some(Err(error)) = self.hgt.map(|height| height.validate()) {
+            report.push(ValidationError::Hgt(error));
+        }
+        if let Some(Err(error)) = self.hcl.as_deref().map(str::parse::<Rgb>) {
+            report.push(ValidationError::Hcl(error));
+        }
+        if let Some(Err(error)) = self.ecl.as_deref().map(str::parse::<EyeColour>) {
+            report.push(ValidationError::Ecl(error));
+        }
+        if let Some(Err(error)) = self.pid.as_deref().map(str::parse::<Pid>) {
+            report.push(ValidationError::Pid(error));
+        }
+
+        let present = [
+            (FieldName::Byr, self.byr.is_some()),
+            (FieldName::Iyr, self.iyr.is_some()),
+            (FieldName::Eyr, self.eyr.is_some()),
+            (FieldName::Hgt, self.hgt.is_some()),
+            (FieldName::Hcl, self.hcl.is_some()),
+            (FieldName::Ecl, self.ecl.is_some()),
+            (FieldName::Pid, self.pid.is_some()),
+        ];
+        for (field, present) in present.iter() {
+            // a field that failed to parse has already been reported, so isn't missing as well.
+            if !present && !report.has_error_for(*field) {
+                report.push(ValidationError::Missing(*field));
+            }
+        }
+    }
+
+    fn parse_fields(string: &str, policy: FieldPolicy, report: &mut ValidationReport) -> Self {
+        let mut passport = Self::default();
+        let mut chosen: Vec<(FieldName, &str)> = Vec::new();
+        for component in string.split_whitespace() {
+            let mut subcomponents = component.splitn(2, ':');
+            let key = subcomponents.next().unwrap();
+            let value = match subcomponents.next() {
+                Some(value) => value,
+                None => {
+                    report.push(ValidationError::MalformedField(component.to_string()));
+                    continue;
+                }
+            };
+            let field = match key.parse::<FieldName>() {
+                Ok(field) => field,
+                Err(error) => {
+                    match policy.unknown {
+                        UnknownPolicy::Reject => report.push(error),
+                        UnknownPolicy::Ignore => report.decide(Decision::UnknownIgnored {
+                            key: key.to_string(),
+                            value: value.to_string(),
+                        }),
+                        UnknownPolicy::KeepAsExtra => {
+                            passport.extras.push((key.to_string(), value.to_string()));
+                            report.decide(Decision::UnknownKept {
+                                key: key.to_string(),
+                                value: value.to_string(),
+                            });
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            let existing = match chosen.iter_mut().find(|(existing, _)| *existing == field) {
+                Some(existing) => existing,
+                None => {
+                    chosen.push((field, value));
+                    continue;
+                }
+            };
+            let (kept, discarded) = match policy.duplicates {
+                DuplicatePolicy::KeepLast => (value, std::mem::replace(&mut existing.1, value)),
+                _ => (existing.1, value),
+            };
+            let (kept, discarded) = (kept.to_string(), discarded.to_string());
+            match policy.duplicates {
+                DuplicatePolicy::Reject => report.push(ValidationError::Duplicate(field)),
+                DuplicatePolicy::Warn => report.decide(Decision::DuplicateWarning {
+                    field,
+                    kept,
+                    discarded,
+                }),
+                DuplicatePolicy::KeepFirst | DuplicatePolicy::KeepLast => {
+                    report.decide(Decision::DuplicateDiscarded {
+                        field,
+                        kept,
+                        discarded,
+                    })
+                }
+            }
+        }
+
+        for (field, value) in chosen {
+            let result = match field {
+                FieldName::Byr => parse_year(value)
+                    .map(|year| passport.byr = Some(year))
+                    .map_err(ValidationError::Byr),
+                FieldName::Iyr => parse_year(value)
+                    .map(|year| passport.iyr = Some(year))
+                    .map_err(ValidationError::Iyr),
+                FieldName::Eyr => parse_year(value)
+                    .map(|year| passport.eyr = Some(year))
+                    .map_err(ValidationError::Eyr),
+                FieldName::Hgt => value
+                    .parse()
+                    .map(|height| passport.hgt = Some(height))
+                    .map_err(ValidationError::Hgt),
+                FieldName::Hcl => {
+                    passport.hcl = Some(value.to_string());
+                    Ok(())
+                }
+                FieldName::Ecl => {
+                    passport.ecl = Some(value.to_string());
+                    Ok(())
+                }
+                FieldName::Pid => {
+                    passport.pid = Some(value.to_string());
+                    Ok(())
+                }
+                FieldName::Cid => {
+                    passport.cid = Some(value.to_string());
+                    Ok(())
+                }
+            };
+            if let Err(error) = result {
+                report.push(error);
+            }
+        }
+        passport
+    }
+}
+
+impl FromStr for Passport {
+    type Err = Error;
+
+    // accepts a whole record, the fields may be split over several lines.
+    fn from_str(string: &str) -> Result<Self> {
+        let mut report = ValidationReport::default();
+        let passport = Self::parse_fields(string, FieldPolicy::default(), &mut report);
+        match report.errors().first() {
+            Some(error) => Err(error.clone().into()),
+            None => Ok(passport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::passport::validation::{
+            EyeColourError, HairColourError, HeightError, PidError, YearError,
+        },
+    };
+
+    #[test]
+    fn test_parsed_values() -> Result<()> {
+        let passport =
+            "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\nbyr:1937 iyr:2017 cid:147 hgt:183cm"
+                .parse::<Passport>()?;
+        assert_eq!(passport.byr(), Some(1937));
+        assert_eq!(passport.hgt(), Some(Height::Cm(183)));
+        assert_eq!(passport.hcl(), Some("#fffffd"));
+        assert_eq!(passport.ecl(), Some("gry"));
+        assert_eq!(passport.pid(), Some("860033327"));
+        assert_eq!(passport.cid(), Some("147"));
+        assert!(passport.is_valid());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validation_is_separate_from_parsing() -> Result<()> {
+        let passport = "eyr:1972 cid:100 hcl:#18171d ecl:amb hgt:170in pid:186cm iyr:2018 byr:1926"
+            .parse::<Passport>()?;
+        assert_eq!(passport.pid(), Some("186cm"));
+        assert!(passport
+            .validate()
+            .errors()
+            .contains(&ValidationError::Pid(PidError::NotADigit('c'))));
+
+        let passport = "hcl:#ABCDEF ecl:zzz pid:0123456789".parse::<Passport>()?;
+        assert_eq!(
+            passport.validate().errors(),
+            [
+                ValidationError::Hcl(HairColourError::BadHexDigit('A')),
+                ValidationError::Ecl(EyeColourError::Unknown("zzz".to_string())),
+                ValidationError::Pid(PidError::WrongLength(10)),
+                ValidationError::Missing(FieldName::Byr),
+                ValidationError::Missing(FieldName::Iyr),
+                ValidationError::Missing(FieldName::Eyr),
+                ValidationError::Missing(FieldName::Hgt),
+            ]
+        );
+
+        let passport = "eyr:1972 hcl:#18171d ecl:amb hgt:170in pid:186000000 iyr:2018 byr:1926"
+            .parse::<Passport>()?;
+        assert!(passport.has_required_fields());
+        assert!(!passport.is_valid());
+        assert_eq!(passport.eyr(), Some(1972));
+        assert_eq!(passport.hgt(), Some(Height::In(170)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_uppercase_hair_colour_agrees_with_other_validators() -> Result<()> {
+        let text = "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#ABCDEF ecl:gry pid:860033327";
+        let (passport, report) = Passport::parse_with_report(text);
+        assert!(!passport.is_valid() && !report.is_valid());
+        let legacy = text.parse::<crate::passport::PassportValidation>()?;
+        assert!(!legacy.valid_full());
+        let schema = crate::passport::schema::Schema::north_pole_credentials();
+        assert!(!schema.validate(&text.parse()?).is_valid());
+        assert!(Passport::parse_with_report(&text.to_lowercase())
+            .1
+            .is_valid());
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_lists_every_violation() {
+        let (passport, report) = Passport::parse_with_report(
+            "byr:1900 iyr:2015 eyr:2025 hgt:180 hcl:#12345g ecl:blu cid:12",
+        );
+        assert_eq!(passport.byr(), Some(1900));
+        assert_eq!(
+            report.errors(),
+            [
+                ValidationError::Hgt(HeightError::MissingUnit),
+                ValidationError::Byr(YearError::OutOfRange {
+                    value: 1900,
+                    min: 1920,
+                    max: 2002
+                }),
+                ValidationError::Hcl(HairColourError::BadHexDigit('g')),
+                ValidationError::Missing(FieldName::Pid),
+            ]
+        );
+        assert_eq!(
+            format!("{}", report),
+            "The height must end with a unit of 'cm' or 'in'.\nThe birth year must be between 1920 and 2002, found 1900.\nThe hair colour contains 'g', which isn't a hex digit.\nThe passport ID (pid) is missing.\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_and_unknown_fields() -> Result<()> {
+        let passport =
+            "hcl:#cfa07d eyr:2025 pid:166559648 iyr:2011 ecl:brn hgt:59in".parse::<Passport>()?;
+        assert!(!passport.has_required_fields());
+        assert_eq!(passport.byr(), None);
+        assert_eq!(
+            format!("{}", "abc:123".parse::<Passport>().unwrap_err()),
+            "'abc' is not a known field."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_and_unknown_policies() {
+        const RECORD: &str =
+            "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 byr:1990 zzz:1";
+        let (passport, report) = Passport::parse_with_report(RECORD);
+        assert_eq!(passport.byr(), Some(1937));
+        assert_eq!(
+            report.errors(),
+            [ValidationError::UnknownField("zzz".to_string())]
+        );
+
+        let policy = FieldPolicy {
+            duplicates: DuplicatePolicy::KeepLast,
+            unknown: UnknownPolicy::KeepAsExtra,
+        };
+        let (passport, report) = Passport::parse_with_policy(RECORD, policy);
+        assert_eq!(passport.byr(), Some(1990));
+        assert_eq!(passport.extras(), [("zzz".to_string(), "1".to_string())]);
+        assert!(report.is_valid());
+        assert_eq!(
+            format!("{}", report),
+            "The birth year (byr) appears more than once, kept '1990' and discarded '1937'.\nKept the unknown field 'zzz:1' as an extra.\n"
+        );
+
+        let policy = FieldPolicy {
+            duplicates: DuplicatePolicy::Warn,
+            unknown: UnknownPolicy::Ignore,
+        };
+        let (passport, report) = Passport::parse_with_policy(RECORD, policy);
+        assert_eq!(passport.byr(), Some(1937));
+        assert!(report.is_valid() && report.has_warnings());
+        assert!(passport.extras().is_empty());
+
+        let policy = FieldPolicy {
+            duplicates: DuplicatePolicy::Reject,
+            unknown: UnknownPolicy::Ignore,
+        };
+        let (_, report) = Passport::parse_with_policy(RECORD, policy);
+        assert_eq!(
+            report.errors(),
+            [ValidationError::Duplicate(FieldName::Byr)]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use {
+    crate::passport::validation::FieldName,
+    std::fmt::{self, Display, Formatter},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    Reject,
+    Warn, // keeps the first value, but flags the duplicate as a warning.
+    KeepFirst,
+    KeepLast,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnknownPolicy {
+    Reject,
+    Ignore,
+    KeepAsExtra,
+}
+
+// applied by both Passport and PassportValidation. the default is the original behaviour, where the
+// first occurrence of a field wins and unknown keys are rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldPolicy {
+    pub duplicates: DuplicatePolicy,
+    pub unknown: UnknownPolicy,
+}
+
+impl Default for FieldPolicy {
+    fn default() -> Self {
+        Self {
+            duplicates: DuplicatePolicy::KeepFirst,
+            unknown: UnknownPolicy::Reject,
+        }
+    }
+}
+
+// what a policy did with a field, rejections are reported as validation errors instead.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decision {
+    DuplicateWarning {
+        field: FieldName,
+        kept: String,
+        discarded: String,
+    },
+    DuplicateDiscarded {
+        field: FieldName,
+        kept: String,
+        discarded: String,
+    },
+    UnknownIgnored {
+        key: String,
+        value: String,
+    },
+    UnknownKept {
+        key: String,
+        value: String,
+    },
+}
+
+impl Decision {
+    pub fn is_warning(&self) -> bool {
+        matches!(self, Decision::DuplicateWarning { .. })
+    }
+}
+
+impl Display for Decision {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Decision::DuplicateWarning {
+                field,
+                kept,
+                discarded,
+            } => write!(
+                f,
+                "Warning: the {} ({}) appears more than once, kept '{}' and ignored '{}'.",
+                field.description(),
+                field.key(),
+                kept,
+                discarded
+            ),
+            Decision::DuplicateDiscarded {
+                field,
+                kept,
+                discarded,
+            } => write!(
+                f,
+                "The {} ({}) appears more than once, kept '{}' and discarded '{}'.",
+                field.description(),
+                field.key(),
+                kept,
+                discarded
+            ),
+            Decision::UnknownIgnored { key, value } => {
+                write!(f, "Ignored the unknown field '{}:{}'.", key, value)
+            }
+            Decision::UnknownKept { key, value } => {
+                write!(f, "Kept the unknown field '{}:{}' as an extra.", key, value)
+            }
+        }
+    }
+}
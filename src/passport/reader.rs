@@ -0,0 +1,139 @@
+use {
+    crate::{
+        iter::chunks::{ChunkVecs, ProvideChunks},
+        passport::record::Record,
+    },
+    std::{
+        io::{BufRead, Error, ErrorKind, Lines, Result},
+        iter::Zip,
+        ops::{RangeFrom, RangeInclusive},
+    },
+};
+
+// a blank line separated record along with where it came from, line numbers start at 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceRecord {
+    number: usize,
+    lines: RangeInclusive<usize>,
+    text: String,
+}
+
+impl SourceRecord {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn lines(&self) -> RangeInclusive<usize> {
+        self.lines.clone()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn record(&self) -> Result<Record> {
+        self.text.parse().map_err(|error| self.locate(error))
+    }
+
+    // prefixes an error with the record number and line range so it can be found in the source.
+    pub fn locate(&self, error: impl std::fmt::Display) -> Error {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "record {} (lines {}-{}): {}",
+                self.number,
+                self.lines.start(),
+                self.lines.end(),
+                error
+            ),
+        )
+    }
+}
+
+type NumberedLine = (usize, Result<String>);
+type NumberedLines<R> = Zip<RangeFrom<usize>, Lines<R>>;
+
+fn is_record_line((_, line): &NumberedLine) -> bool {
+    match line {
+        Ok(line) => !line.trim().is_empty(),
+        Err(_) => true, // kept so that the error is reported with the record.
+    }
+}
+
+pub struct PassportReader<R> {
+    chunks: ChunkVecs<NumberedLines<R>, fn(&NumberedLine) -> bool>,
+    number: usize,
+}
+
+impl<R: BufRead> PassportReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            chunks: (1..).zip(reader.lines()).chunk_vecs(is_record_line),
+            number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PassportReader<R> {
+    type Item = Result<SourceRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        self.number += 1;
+        let first = chunk.first().map(|(line_number, _)| *line_number).unwrap();
+        let last = chunk.last().map(|(line_number, _)| *line_number).unwrap();
+        let mut lines = Vec::with_capacity(chunk.len());
+        for (_, line) in chunk {
+            match line {
+                Ok(line) => lines.push(line),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        Some(Ok(SourceRecord {
+            number: self.number,
+            lines: first..=last,
+            text: lines.join("\n"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm";
+
+    #[test]
+    fn test_records_and_line_ranges() -> Result<()> {
+        let records = PassportReader::new(INPUT.as_bytes()).collect::<Result<Vec<_>>>()?;
+        assert_eq!(records.len(), 3); // the last record has no trailing blank line.
+        assert_eq!(records[0].number(), 1);
+        assert_eq!(records[0].lines(), 1..=2);
+        assert_eq!(records[1].lines(), 4..=5);
+        assert_eq!(records[2].number(), 3);
+        assert_eq!(records[2].lines(), 8..=11);
+        assert_eq!(records[2].record()?.get("hgt"), Some("179cm"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors_point_to_the_source() {
+        let mut records = PassportReader::new("\n\nbyr:1937\niyr\n".as_bytes());
+        let error = records.next().unwrap().unwrap().record().unwrap_err();
+        assert_eq!(
+            format!("{}", error),
+            "record 1 (lines 3-4): Field should be of the form <key>:<value>: iyr"
+        );
+        assert!(records.next().is_none());
+    }
+}
@@ -0,0 +1,66 @@
+use std::{
+    io::{Error, ErrorKind, Result},
+    str::FromStr,
+};
+
+// The raw key:value pairs of a single document, in the order they appeared.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Record {
+    fields: Vec<(String, String)>,
+}
+
+impl Record {
+    pub fn push(&mut self, key: &str, value: &str) {
+        self.fields.push((key.to_string(), value.to_string()));
+    }
+
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl FromStr for Record {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        let mut record = Self::default();
+        for component in string.split_whitespace() {
+            match component.find(':') {
+                Some(index) => record.push(&component[..index], &component[index + 1..]),
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Field should be of the form <key>:<value>: {}", component),
+                    ))
+                }
+            }
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_record() -> Result<()> {
+        let record = "ecl:gry pid:860033327\nbyr:1937 ecl:brn".parse::<Record>()?;
+        assert_eq!(record.fields().len(), 4);
+        assert_eq!(record.get("ecl"), Some("gry"));
+        assert_eq!(record.get("hgt"), None);
+        assert!("ecl:gry pid".parse::<Record>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use {
+    crate::passport::{fields::EyeColour, model::Passport, validation::FieldName},
+    std::fmt::{self, Display, Formatter},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    field: FieldName,
+    original: String,
+    replacement: String,
+    reason: &'static str,
+    confidence: f64,
+}
+
+impl Suggestion {
+    pub fn field(&self) -> FieldName {
+        self.field
+    }
+
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+
+    // between 0 and 1, how likely the replacement is what was meant.
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} -> {}:{} ({}, {:.0}% confident)",
+            self.field.key(),
+            self.original,
+            self.field.key(),
+            self.replacement,
+            self.reason,
+            self.confidence * 100.0
+        )
+    }
+}
+
+const EYE_COLOUR_NAMES: [(&str, EyeColour); 8] = [
+    ("amber", EyeColour::Amber),
+    ("blue", EyeColour::Blue),
+    ("brown", EyeColour::Brown),
+    ("grey", EyeColour::Grey),
+    ("gray", EyeColour::Grey),
+    ("green", EyeColour::Green),
+    ("hazel", EyeColour::Hazel),
+    ("other", EyeColour::Other),
+];
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + if a == *b { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// candidate fixes for a single field, not yet checked against the rules.
+fn candidates(field: FieldName, value: &str) -> Vec<(String, &'static str, f64)> {
+    let mut candidates = Vec::new();
+    let lowercase = value.to_lowercase();
+    if lowercase != value {
+        candidates.push((lowercase.clone(), "lowercased", 0.95));
+    }
+    match field {
+        FieldName::Byr | FieldName::Iyr | FieldName::Eyr => {
+            if value.len() == 2 && value.chars().all(|c| c.is_ascii_digit()) {
+                for century in ["19", "20"].iter() {
+                    candidates.push((format!("{}{}", century, value), "added the century", 0.6));
+                }
+            }
+        }
+        FieldName::Hgt => {
+            if value.chars().all(|c| c.is_ascii_digit()) {
+                candidates.push((format!("{}cm", value), "added the missing unit", 0.9));
+                candidates.push((format!("{}in", value), "added the missing unit", 0.9));
+            } else if let Some(number) = lowercase.strip_suffix("cm") {
+                candidates.push((format!("{}in", number), "swapped the unit", 0.6));
+            } else if let Some(number) = lowercase.strip_suffix("in") {
+                candidates.push((format!("{}cm", number), "swapped the unit", 0.6));
+            }
+        }
+        FieldName::Hcl => {
+            if !lowercase.starts_with('#') {
+                candidates.push((format!("#{}", lowercase), "added the missing '#'", 0.9));
+            }
+        }
+        FieldName::Ecl => {
+            for (name, colour) in EYE_COLOUR_NAMES.iter() {
+                if lowercase == *name {
+                    candidates.push((colour.code().to_string(), "used the code", 0.9));
+                }
+            }
+            for colour in EyeColour::ALL.iter() {
+                let confidence = match edit_distance(&lowercase, colour.code()) {
+                    1 => 0.7,
+                    2 => 0.3,
+                    _ => continue,
+                };
+                candidates.push((colour.code().to_string(), "closest code", confidence));
+            }
+        }
+        FieldName::Pid => {
+            // letters which are easily mistaken for digits when scanned or typed.
+            let digits = value
+                .chars()
+                .map(|c| match c {
+                    'O' | 'o' => '0',
+                    'I' | 'l' | 'i' => '1',
+                    'S' | 's' => '5',
+                    'B' => '8',
+                    c => c,
+                })
+                .collect::<String>();
+            if digits != value {
+                candidates.push((digits.clone(), "replaced letters with digits", 0.6));
+            }
+            match digits.len() {
+                8 => candidates.push((format!("0{}", digits), "restored a leading zero", 0.7)),
+                10 if digits.starts_with('0') => {
+                    candidates.push((digits[1..].to_string(), "removed a leading zero", 0.5))
+                }
+                _ => {}
+            }
+        }
+        FieldName::Cid => {}
+    }
+    candidates
+}
+
+// suggestions for a value which fails validation, best first. only replacements which pass the
+// rules are suggested, so an empty list means there's no likely fix.
+pub fn suggest(field: FieldName, value: &str) -> Vec<Suggestion> {
+    if Passport::validate_field(field, value).is_ok() {
+        return Vec::new();
+    }
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for (replacement, reason, confidence) in candidates(field, value) {
+        let seen = suggestions
+            .iter()
+            .any(|suggestion| suggestion.replacement == replacement);
+        if !seen && Passport::validate_field(field, &replacement).is_ok() {
+            suggestions.push(Suggestion {
+                field,
+                original: value.to_string(),
+                replacement,
+                reason,
+                confidence,
+            });
+        }
+    }
+    suggestions.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+    suggestions
+}
+
+// suggestions for every invalid value in a record, in the order the fields appear.
+pub fn suggest_repairs(record: &str) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for component in record.split_whitespace() {
+        let mut subcomponents = component.splitn(2, ':');
+        let key = subcomponents.next().unwrap();
+        if let (Ok(field), Some(value)) = (key.parse::<FieldName>(), subcomponents.next()) {
+            suggestions.extend(suggest(field, value));
+        }
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacements(field: FieldName, value: &str) -> Vec<(String, f64)> {
+        suggest(field, value)
+            .into_iter()
+            .map(|suggestion| {
+                (
+                    suggestion.replacement().to_string(),
+                    suggestion.confidence(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_suggestions() {
+        assert_eq!(
+            replacements(FieldName::Hgt, "180"),
+            [("180cm".to_string(), 0.9)]
+        );
+        assert_eq!(
+            replacements(FieldName::Hgt, "70"),
+            [("70in".to_string(), 0.9)]
+        );
+        assert_eq!(
+            replacements(FieldName::Hgt, "180in"),
+            [("180cm".to_string(), 0.6)]
+        );
+        assert_eq!(replacements(FieldName::Hgt, "18"), []);
+        assert_eq!(replacements(FieldName::Ecl, "gry"), []);
+        assert_eq!(
+            replacements(FieldName::Ecl, "hzel"),
+            [("hzl".to_string(), 0.7)]
+        );
+        assert_eq!(
+            replacements(FieldName::Ecl, "Hazel"),
+            [("hzl".to_string(), 0.9)]
+        );
+        assert_eq!(
+            replacements(FieldName::Pid, "12345678"),
+            [("012345678".to_string(), 0.7)]
+        );
+        assert_eq!(
+            replacements(FieldName::Pid, "O12345678"),
+            [("012345678".to_string(), 0.6)]
+        );
+        assert_eq!(
+            replacements(FieldName::Hcl, "a97842"),
+            [("#a97842".to_string(), 0.9)]
+        );
+    }
+
+    #[test]
+    fn test_suggest_repairs_for_a_record() {
+        let suggestions = suggest_repairs("byr:1937 hgt:183 ecl:brwn pid:76075310 iyr:2017");
+        let lines = suggestions
+            .iter()
+            .map(|suggestion| suggestion.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "hgt:183 -> hgt:183cm (added the missing unit, 90% confident)",
+                "ecl:brwn -> ecl:brn (closest code, 70% confident)",
+                "ecl:brwn -> ecl:grn (closest code, 30% confident)",
+                "pid:76075310 -> pid:076075310 (restored a leading zero, 70% confident)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,502 @@
+pub mod cross_field;
+
+use {
+    crate::{
+        passport::{
+            record::Record,
+            schema::cross_field::{CrossFieldRule, Inconsistency},
+        },
+        pattern::Pattern,
+    },
+    std::{
+        fmt::{self, Display, Formatter},
+        io::{Error, ErrorKind, Result},
+        str::FromStr,
+    },
+};
+
+const NORTH_POLE_CREDENTIALS: &str = "
+[north-pole-credentials]
+byr required pattern \\d{4} and range 1920 2002
+iyr required pattern \\d{4} and range 2010 2020
+eyr required pattern \\d{4} and range 2020 2030
+hgt required units cm 150 193 in 59 76
+hcl required pattern #[0-9a-f]{6}
+ecl required enum amb blu brn gry grn hzl oth
+pid required pattern \\d{9}
+cid optional
+";
+
+const PASSPORT: &str = "
+[passport]
+byr required pattern \\d{4} and range 1920 2002
+iyr required pattern \\d{4} and range 2010 2020
+eyr required pattern \\d{4} and range 2020 2030
+hgt required units cm 150 193 in 59 76
+hcl required pattern #[0-9a-f]{6}
+ecl required enum amb blu brn gry grn hzl oth
+pid required pattern \\d{9}
+cid required
+";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitRange {
+    unit: String,
+    min: i64,
+    max: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Validator {
+    Range { min: i64, max: i64 },
+    Pattern(Pattern),
+    OneOf(Vec<String>),
+    Units(Vec<UnitRange>),
+}
+
+fn parse_integer(string: &str) -> Option<i64> {
+    let digits = string.strip_prefix('-').unwrap_or(string);
+    if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+        string.parse().ok()
+    } else {
+        None
+    }
+}
+
+impl Validator {
+    // returns the reason the value was rejected.
+    pub fn check(&self, value: &str) -> std::result::Result<(), String> {
+        match self {
+            Validator::Range { min, max } => match parse_integer(value) {
+                Some(number) if (*min..=*max).contains(&number) => Ok(()),
+                Some(number) => Err(format!(
+                    "must be between {} and {}, found {}",
+                    min, max, number
+                )),
+                None => Err("must be a whole number".to_string()),
+            },
+            Validator::Pattern(pattern) => {
+                if pattern.matches(value) {
+                    Ok(())
+                } else {
+                    Err(format!("must match the pattern {}", pattern))
+                }
+            }
+            Validator::OneOf(options) => {
+                if options.iter().any(|option| option == value) {
+                    Ok(())
+                } else {
+                    Err(format!("must be one of {}", options.join(", ")))
+                }
+            }
+            Validator::Units(ranges) => {
+                let range = ranges
+                    .iter()
+                    .find(|range| value.ends_with(&range.unit as &str))
+                    .ok_or_else(|| {
+                        let units = ranges.iter().map(|range| &range.unit as &str);
+                        format!(
+                            "must end with one of the units {}",
+                            units.collect::<Vec<_>>().join(", ")
+                        )
+                    })?;
+                match parse_integer(&value[..value.len() - range.unit.len()]) {
+                    Some(number) if (range.min..=range.max).contains(&number) => Ok(()),
+                    Some(number) => Err(format!(
+                        "must be between {}{} and {}{}, found {}{}",
+                        range.min, range.unit, range.max, range.unit, number, range.unit
+                    )),
+                    None => Err("must be a whole number followed by a unit".to_string()),
+                }
+            }
+        }
+    }
+
+    fn parse(tokens: &[&str]) -> Result<Self> {
+        let invalid = |reason: &str| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid validator '{}', {}", tokens.join(" "), reason),
+            )
+        };
+        let integer =
+            |token: &str| parse_integer(token).ok_or_else(|| invalid("expected a number"));
+
+        match tokens {
+            ["range", min, max] => Ok(Validator::Range {
+                min: integer(min)?,
+                max: integer(max)?,
+            }),
+            ["pattern", pattern] => Ok(Validator::Pattern(pattern.parse()?)),
+            ["enum", options @ ..] if !options.is_empty() => Ok(Validator::OneOf(
+                options.iter().map(|option| option.to_string()).collect(),
+            )),
+            ["units", ranges @ ..] if !ranges.is_empty() && ranges.len() % 3 == 0 => {
+                let mut units = Vec::new();
+                for range in ranges.chunks(3) {
+                    units.push(UnitRange {
+                        unit: range[0].to_string(),
+                        min: integer(range[1])?,
+                        max: integer(range[2])?,
+                    });
+                }
+                Ok(Validator::Units(units))
+            }
+            _ => Err(invalid(
+                "expected range <min> <max>, pattern <pattern>, enum <values...> or units (<unit> <min> <max>)...",
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldRule {
+    key: String,
+    required: bool,
+    validators: Vec<Validator>,
+}
+
+impl FieldRule {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    pub fn validators(&self) -> &[Validator] {
+        &self.validators
+    }
+}
+
+// each rule is of the form <key> <required|optional> [<validator> [and <validator>]...]
+impl FromStr for FieldRule {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        let tokens = string.split_whitespace().collect::<Vec<_>>();
+        let required = match tokens.get(1) {
+            Some(&"required") => true,
+            Some(&"optional") => false,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Rule should be of the form <key> <required|optional> [validators]: {}",
+                        string
+                    ),
+                ))
+            }
+        };
+        let validators = if tokens.len() > 2 {
+            tokens[2..]
+                .split(|token| *token == "and")
+                .map(Validator::parse)
+                .collect::<Result<_>>()?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            key: tokens[0].to_string(),
+            required,
+            validators,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaError {
+    Missing(String),
+    Unknown(String),
+    Invalid {
+        key: String,
+        value: String,
+        reason: String,
+    },
+    Inconsistent(Inconsistency),
+    Overflow(String), // a cross field rule whose arithmetic overflowed, so it can't be checked.
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SchemaError::Missing(key) => write!(f, "{} is missing", key),
+            SchemaError::Unknown(key) => write!(f, "{} is not a known field", key),
+            SchemaError::Invalid { key, value, reason } => {
+                write!(f, "{} {}: {}", key, reason, value)
+            }
+            SchemaError::Inconsistent(inconsistency) => write!(f, "{}", inconsistency),
+            SchemaError::Overflow(rule) => {
+                write!(f, "{} can't be checked, the arithmetic overflows", rule)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaReport {
+    errors: Vec<SchemaError>,
+}
+
+impl SchemaReport {
+    pub fn errors(&self) -> &[SchemaError] {
+        &self.errors
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl Display for SchemaReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema {
+    name: String,
+    fields: Vec<FieldRule>,
+    rules: Vec<CrossFieldRule>,
+}
+
+impl Schema {
+    pub fn north_pole_credentials() -> Self {
+        Self::builtin(NORTH_POLE_CREDENTIALS)
+    }
+
+    pub fn passport() -> Self {
+        Self::builtin(PASSPORT)
+    }
+
+    fn builtin(config: &str) -> Self {
+        config
+            .parse::<Schemas>()
+            .unwrap() // the built in configs are covered by tests.
+            .schemas
+            .remove(0)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn fields(&self) -> &[FieldRule] {
+        &self.fields
+    }
+
+    pub fn rules(&self) -> &[CrossFieldRule] {
+        &self.rules
+    }
+
+    pub fn validate(&self, record: &Record) -> SchemaReport {
+        let mut errors = Vec::new();
+        for (key, value) in record.fields() {
+            match self.fields.iter().find(|rule| rule.key == *key) {
+                None => errors.push(SchemaError::Unknown(key.clone())),
+                Some(rule) => {
+                    for validator in &rule.validators {
+                        if let Err(reason) = validator.check(value) {
+                            errors.push(SchemaError::Invalid {
+                                key: key.clone(),
+                                value: value.clone(),
+                                reason,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        for rule in &self.fields {
+            if rule.required && record.get(&rule.key).is_none() {
+                errors.push(SchemaError::Missing(rule.key.clone()));
+            }
+        }
+        for rule in &self.rules {
+            if let Err(error) = rule.check(record) {
+                errors.push(error);
+            }
+        }
+        SchemaReport { errors }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schemas {
+    schemas: Vec<Schema>,
+}
+
+impl Schemas {
+    pub fn get(&self, name: &str) -> Option<&Schema> {
+        self.schemas.iter().find(|schema| schema.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Schema> {
+        self.schemas.iter()
+    }
+}
+
+// a config is a list of [name] sections each followed by field rules and cross field rules
+// ("rule iyr <= eyr"), '#' starts a comment line.
+impl FromStr for Schemas {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        let mut schemas: Vec<Schema> = Vec::new();
+        for (line_number, line) in (1..).zip(string.lines()) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let with_line = |error: Error| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("line {}: {}", line_number, error),
+                )
+            };
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                schemas.push(Schema {
+                    name: name.trim().to_string(),
+                    fields: Vec::new(),
+                    rules: Vec::new(),
+                });
+            } else {
+                let schema = schemas.last_mut().ok_or_else(|| {
+                    with_line(Error::new(
+                        ErrorKind::InvalidData,
+                        "Rule appears before any [schema] section",
+                    ))
+                })?;
+                match line.strip_prefix("rule ") {
+                    Some(rule) => schema.rules.push(rule.parse().map_err(with_line)?),
+                    None => schema.fields.push(line.parse().map_err(with_line)?),
+                }
+            }
+        }
+        // rules may come before the fields they use, so they're only checked once a schema is read.
+        for schema in &schemas {
+            for rule in &schema.rules {
+                if let Some(key) = rule
+                    .fields()
+                    .find(|key| schema.fields.iter().all(|field| field.key != *key))
+                {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Rule '{}' in [{}] uses the field {}, which the schema doesn't define",
+                            rule, schema.name, key
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(Self { schemas })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::passport::model::Passport};
+
+    const RECORDS: [&str; 4] = [
+        "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd byr:1937 iyr:2017 cid:147 hgt:183cm",
+        "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884 hcl:#cfa07d byr:1929",
+        "hcl:#ae17e1 iyr:2013 eyr:2024 ecl:brn pid:760753108 byr:1931 hgt:179cm",
+        "eyr:1972 cid:100 hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926",
+    ];
+
+    #[test]
+    fn test_north_pole_credentials_matches_passport_model() -> Result<()> {
+        let schema = Schema::north_pole_credentials();
+        for record in RECORDS.iter() {
+            let (passport, _) = Passport::parse_with_report(record);
+            let report = schema.validate(&record.parse()?);
+            assert_eq!(report.is_valid(), passport.is_valid(), "{}", record);
+        }
+        assert!(!Schema::passport().validate(&RECORDS[2].parse()?).is_valid());
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_errors() -> Result<()> {
+        let report = Schema::north_pole_credentials().validate(&RECORDS[3].parse()?);
+        assert_eq!(
+            report.errors(),
+            [
+                SchemaError::Invalid {
+                    key: "eyr".to_string(),
+                    value: "1972".to_string(),
+                    reason: "must be between 2020 and 2030, found 1972".to_string()
+                },
+                SchemaError::Invalid {
+                    key: "hgt".to_string(),
+                    value: "170".to_string(),
+                    reason: "must end with one of the units cm, in".to_string()
+                },
+                SchemaError::Invalid {
+                    key: "pid".to_string(),
+                    value: "186cm".to_string(),
+                    reason: "must match the pattern \\d{9}".to_string()
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_config() -> Result<()> {
+        let schemas = "# document types\n[library card]\nnum required pattern [A-Z]{2}\\d{4}\nexp required range 2020 2030\nnote optional\n\n[badge]\nlvl required enum 1 2 3\n".parse::<Schemas>()?;
+        let card = schemas.get("library card").unwrap();
+        assert_eq!(card.fields().len(), 3);
+        assert!(card.validate(&"num:AB1234 exp:2025".parse()?).is_valid());
+        let report = card.validate(&"num:ab1234 zzz:1".parse()?);
+        assert_eq!(
+            format!("{}", report),
+            "num must match the pattern [A-Z]{2}\\d{4}: ab1234\nzzz is not a known field\nexp is missing\n"
+        );
+        assert!(schemas.get("badge").is_some());
+
+        let error = "[badge]\nlvl mandatory".parse::<Schemas>().unwrap_err();
+        assert!(format!("{}", error).starts_with("line 2: "));
+        assert!("lvl required".parse::<Schemas>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cross_field_rules_in_config() -> Result<()> {
+        let config = format!(
+            "{}rule iyr <= eyr\nrule byr < iyr\nrule iyr - byr >= 18\n",
+            NORTH_POLE_CREDENTIALS
+        );
+        let schemas = config.parse::<Schemas>()?;
+        let schema = schemas.get("north-pole-credentials").unwrap();
+        assert_eq!(schema.rules().len(), 3);
+
+        // passes every field rule, but was issued when the holder was 10.
+        let record = "byr:2002 iyr:2012 eyr:2025 hgt:150cm hcl:#abcdef ecl:oth pid:000000001";
+        assert!(Schema::north_pole_credentials()
+            .validate(&record.parse()?)
+            .is_valid());
+        assert_eq!(
+            format!("{}", schema.validate(&record.parse()?)),
+            "iyr - byr >= 18 doesn't hold, the two sides are 10 and 18\n"
+        );
+
+        let error = format!("{}rule iyr <= exp\n", NORTH_POLE_CREDENTIALS)
+            .parse::<Schemas>()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Rule 'iyr <= exp' in [north-pole-credentials] uses the field exp, which the schema doesn't define"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+use {
+    crate::passport::{
+        record::Record,
+        schema::{parse_integer, SchemaError},
+    },
+    std::{
+        fmt::{self, Display, Formatter},
+        io::{Error, ErrorKind, Result},
+        str::FromStr,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    pub fn holds(&self, left: i64, right: i64) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Greater => left > right,
+        }
+    }
+}
+
+impl FromStr for Comparison {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "<" => Ok(Comparison::Less),
+            "<=" => Ok(Comparison::LessOrEqual),
+            "==" => Ok(Comparison::Equal),
+            "!=" => Ok(Comparison::NotEqual),
+            ">=" => Ok(Comparison::GreaterOrEqual),
+            ">" => Ok(Comparison::Greater),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unknown comparison: {}", string),
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Term {
+    Field(String),
+    Number(i64),
+}
+
+// a sum of fields and numbers, e.g. "iyr - byr" or "byr + 18".
+#[derive(Clone, Debug, PartialEq)]
+struct Expression {
+    terms: Vec<(i64, Term)>,
+}
+
+impl Expression {
+    // Ok(None) when a field is missing or not a whole number, which the field rules already report,
+    // and Err(()) if the sum overflows.
+    fn evaluate(&self, record: &Record) -> std::result::Result<Option<i64>, ()> {
+        let mut total = 0i64;
+        for (sign, term) in &self.terms {
+            let value = match term {
+                Term::Field(key) => match record.get(key).and_then(parse_integer) {
+                    Some(value) => value,
+                    None => return Ok(None),
+                },
+                Term::Number(number) => *number,
+            };
+            total = sign
+                .checked_mul(value)
+                .and_then(|value| total.checked_add(value))
+                .ok_or(())?;
+        }
+        Ok(Some(total))
+    }
+
+    fn fields(&self) -> impl Iterator<Item = &str> {
+        self.terms.iter().filter_map(|(_, term)| match term {
+            Term::Field(key) => Some(key.as_str()),
+            Term::Number(_) => None,
+        })
+    }
+
+    fn parse(tokens: &[&str]) -> Option<Self> {
+        let mut terms = Vec::new();
+        let mut sign = 1;
+        for (index, token) in tokens.iter().enumerate() {
+            if index % 2 == 1 {
+                sign = match *token {
+                    "+" => 1,
+                    "-" => -1,
+                    _ => return None,
+                };
+            } else if let Some(number) = parse_integer(token) {
+                terms.push((sign, Term::Number(number)));
+            } else if token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                terms.push((sign, Term::Field(token.to_string())));
+            } else {
+                return None;
+            }
+        }
+        if tokens.len() % 2 == 1 {
+            Some(Self { terms })
+        } else {
+            None
+        }
+    }
+}
+
+// a rule relating several fields of the same document, e.g. "iyr - byr >= 18".
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossFieldRule {
+    source: String,
+    left: Expression,
+    comparison: Comparison,
+    right: Expression,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inconsistency {
+    rule: String,
+    left: i64,
+    right: i64,
+}
+
+impl Inconsistency {
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    // the values the two sides of the rule evaluated to.
+    pub fn left(&self) -> i64 {
+        self.left
+    }
+
+    pub fn right(&self) -> i64 {
+        self.right
+    }
+}
+
+impl Display for Inconsistency {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} doesn't hold, the two sides are {} and {}",
+            self.rule, self.left, self.right
+        )
+    }
+}
+
+impl CrossFieldRule {
+    // the keys of the fields the rule uses, in the order they're written.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.left.fields().chain(self.right.fields())
+    }
+
+    pub fn check(&self, record: &Record) -> std::result::Result<(), SchemaError> {
+        let overflow = |()| SchemaError::Overflow(self.source.clone());
+        let left = self.left.evaluate(record).map_err(overflow)?;
+        let right = self.right.evaluate(record).map_err(overflow)?;
+        match (left, right) {
+            (Some(left), Some(right)) if !self.comparison.holds(left, right) => {
+                Err(SchemaError::Inconsistent(Inconsistency {
+                    rule: self.source.clone(),
+                    left,
+                    right,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Display for CrossFieldRule {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+// the operators and operands must be separated by whitespace.
+impl FromStr for CrossFieldRule {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        let tokens = string.split_whitespace().collect::<Vec<_>>();
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Rule should be of the form <expression> <comparison> <expression>: {}",
+                    string
+                ),
+            )
+        };
+        let index = tokens
+            .iter()
+            .position(|token| token.parse::<Comparison>().is_ok())
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            source: tokens.join(" "),
+            left: Expression::parse(&tokens[..index]).ok_or_else(invalid)?,
+            comparison: tokens[index].parse()?,
+            right: Expression::parse(&tokens[index + 1..]).ok_or_else(invalid)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_field_rules() -> Result<()> {
+        let record = "byr:1999 iyr:2010 eyr:2005".parse::<Record>()?;
+        assert!("byr < iyr"
+            .parse::<CrossFieldRule>()?
+            .check(&record)
+            .is_ok());
+        assert_eq!(
+            "iyr <= eyr".parse::<CrossFieldRule>()?.check(&record),
+            Err(SchemaError::Inconsistent(Inconsistency {
+                rule: "iyr <= eyr".to_string(),
+                left: 2010,
+                right: 2005
+            }))
+        );
+        let age = "iyr - byr >= 18".parse::<CrossFieldRule>()?;
+        match age.check(&record) {
+            Err(SchemaError::Inconsistent(inconsistency)) => {
+                assert_eq!(inconsistency.rule(), "iyr - byr >= 18");
+                assert_eq!((inconsistency.left(), inconsistency.right()), (11, 18));
+            }
+            result => panic!("expected an inconsistency, found {:?}", result),
+        }
+        assert_eq!(age.fields().collect::<Vec<_>>(), ["iyr", "byr"]);
+        // missing fields are left to the field rules.
+        assert!(age.check(&"iyr:2010".parse()?).is_ok());
+
+        assert!("iyr <= ".parse::<CrossFieldRule>().is_err());
+        assert!("iyr - <= eyr".parse::<CrossFieldRule>().is_err());
+        assert!("iyr eyr".parse::<CrossFieldRule>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow_fails_the_rule() -> Result<()> {
+        let record = "byr:9223372036854775807 iyr:1".parse::<Record>()?;
+        let rule = "byr + iyr > 0".parse::<CrossFieldRule>()?;
+        assert_eq!(
+            rule.check(&record),
+            Err(SchemaError::Overflow("byr + iyr > 0".to_string()))
+        );
+        assert!("byr - iyr > 0"
+            .parse::<CrossFieldRule>()?
+            .check(&record)
+            .is_ok());
+        Ok(())
+    }
+}
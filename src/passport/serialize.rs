@@ -0,0 +1,213 @@
+use {
+    crate::{
+        io::csv,
+        passport::{fields::Height, model::Passport, validation::FieldName},
+    },
+    std::{
+        fmt::{self, Display, Formatter},
+        io::{Result, Write},
+    },
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeightUnits {
+    #[default]
+    AsGiven,
+    Centimetres,
+    Inches,
+}
+
+impl HeightUnits {
+    // every height is written in the chosen units. conversions round to the nearest whole unit,
+    // which can change validity: 149cm and 194cm are both outside the range, but become 59in and
+    // 76in, which are inside it. validate before normalising if that matters.
+    pub fn convert(self, height: Height) -> Height {
+        match self {
+            HeightUnits::AsGiven => height,
+            HeightUnits::Centimetres => height.to_centimetres(),
+            HeightUnits::Inches => height.to_inches(),
+        }
+    }
+}
+
+// a passport written as key:value pairs in canonical field order, with the heights in the chosen
+// units. extras follow the known fields in the order they were read.
+pub struct Normalised<'a> {
+    passport: &'a Passport,
+    heights: HeightUnits,
+}
+
+impl<'a> Normalised<'a> {
+    fn values(&self) -> [(FieldName, Option<String>); 8] {
+        let passport = self.passport;
+        let height = passport.hgt().map(|height| self.heights.convert(height));
+        [
+            (FieldName::Byr, passport.byr().map(|year| year.to_string())),
+            (FieldName::Iyr, passport.iyr().map(|year| year.to_string())),
+            (FieldName::Eyr, passport.eyr().map(|year| year.to_string())),
+            (FieldName::Hgt, height.as_ref().map(Height::to_string)),
+            (FieldName::Hcl, passport.hcl().map(str::to_string)),
+            (FieldName::Ecl, passport.ecl().map(str::to_string)),
+            (FieldName::Pid, passport.pid().map(str::to_string)),
+            (FieldName::Cid, passport.cid().map(str::to_string)),
+        ]
+    }
+}
+
+impl<'a> Display for Normalised<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let values = self.values();
+        let known = values
+            .iter()
+            .filter_map(|(field, value)| Some((field.key(), value.as_deref()?)));
+        let extras = self
+            .passport
+            .extras()
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()));
+        for (index, (key, value)) in known.chain(extras).enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}:{}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl Passport {
+    pub fn normalised(&self, heights: HeightUnits) -> Normalised<'_> {
+        Normalised {
+            passport: self,
+            heights,
+        }
+    }
+}
+
+impl Display for Passport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.normalised(HeightUnits::AsGiven))
+    }
+}
+
+// one record per line, with a blank line between records so the output can be read back.
+pub fn write_records<'a, W: Write>(
+    writer: &mut W,
+    passports: impl IntoIterator<Item = &'a Passport>,
+    heights: HeightUnits,
+) -> Result<()> {
+    for (index, passport) in passports.into_iter().enumerate() {
+        if index > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "{}", passport.normalised(heights))?;
+    }
+    Ok(())
+}
+
+// one column per known field, missing fields are left empty and extras aren't written.
+pub fn write_csv<'a, W: Write>(
+    writer: &mut W,
+    passports: impl IntoIterator<Item = &'a Passport>,
+    heights: HeightUnits,
+) -> Result<()> {
+    let header = FieldName::ALL.iter().map(FieldName::key);
+    writeln!(writer, "{}", header.collect::<Vec<_>>().join(","))?;
+    for passport in passports {
+        let values = passport.normalised(heights).values();
+        let cells = values
+            .iter()
+            .map(|(_, value)| value.as_deref().map(csv::escape).unwrap_or_default());
+        writeln!(writer, "{}", cells.collect::<Vec<_>>().join(","))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::passport::{
+            policy::{FieldPolicy, UnknownPolicy},
+            reader::PassportReader,
+        },
+    };
+
+    const INPUT: &str = "hgt:70in cid:147 ecl:gry pid:060033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017
+
+iyr:2013 hcl:#cfa07d byr:1929 hgt:183cm";
+
+    fn passports() -> Result<Vec<Passport>> {
+        PassportReader::new(INPUT.as_bytes())
+            .map(|record| record?.text().parse())
+            .collect()
+    }
+
+    #[test]
+    fn test_canonical_records() -> Result<()> {
+        let passports = passports()?;
+        assert_eq!(
+            passports[0].to_string(),
+            "byr:1937 iyr:2017 eyr:2020 hgt:70in hcl:#fffffd ecl:gry pid:060033327 cid:147"
+        );
+
+        let mut output = Vec::new();
+        write_records(&mut output, &passports, HeightUnits::Centimetres)?;
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            "byr:1937 iyr:2017 eyr:2020 hgt:178cm hcl:#fffffd ecl:gry pid:060033327 cid:147\n\nbyr:1929 iyr:2013 hgt:183cm hcl:#cfa07d\n"
+        );
+
+        // the output can be read back in to the same passports.
+        let reread = PassportReader::new(output.as_bytes())
+            .map(|record| record?.text().parse())
+            .collect::<Result<Vec<Passport>>>()?;
+        assert_eq!(reread[1], passports[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_conversion_uses_one_unit() {
+        let inches = |value| HeightUnits::Inches.convert(Height::Cm(value));
+        let centimetres = |value| HeightUnits::Centimetres.convert(Height::In(value));
+        // rounding brings the cm values either side of the range inside the inch range.
+        assert_eq!(inches(149), Height::In(59));
+        assert_eq!(inches(150), Height::In(59));
+        assert_eq!(inches(193), Height::In(76));
+        assert_eq!(inches(194), Height::In(76));
+        assert_eq!(centimetres(58), Height::Cm(147));
+        assert_eq!(centimetres(59), Height::Cm(150));
+        assert_eq!(centimetres(76), Height::Cm(193));
+        assert_eq!(centimetres(77), Height::Cm(196));
+        for value in 100..250 {
+            assert!(matches!(inches(value), Height::In(_)));
+            assert!(matches!(centimetres(value), Height::Cm(_)));
+        }
+    }
+
+    #[test]
+    fn test_csv() -> Result<()> {
+        let policy = FieldPolicy {
+            unknown: UnknownPolicy::KeepAsExtra,
+            ..FieldPolicy::default()
+        };
+        let (with_extra, _) = Passport::parse_with_policy("cid:a,\"b\" zzz:1 hgt:183cm", policy);
+        assert_eq!(with_extra.to_string(), "hgt:183cm cid:a,\"b\" zzz:1");
+
+        let mut passports = passports()?;
+        passports.push(with_extra);
+        let mut output = Vec::new();
+        write_csv(&mut output, &passports, HeightUnits::Inches)?;
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "byr,iyr,eyr,hgt,hcl,ecl,pid,cid
+1937,2017,2020,70in,#fffffd,gry,060033327,147
+1929,2013,,72in,#cfa07d,,,
+,,,72in,,,,\"a,\"\"b\"\"\"
+"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+use {
+    crate::passport::policy::Decision,
+    std::{
+        error,
+        fmt::{self, Display, Formatter},
+        io,
+        str::FromStr,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FieldName {
+    Byr,
+    Iyr,
+    Eyr,
+    Hgt,
+    Hcl,
+    Ecl,
+    Pid,
+    Cid,
+}
+
+impl FieldName {
+    pub const ALL: [FieldName; 8] = [
+        FieldName::Byr,
+        FieldName::Iyr,
+        FieldName::Eyr,
+        FieldName::Hgt,
+        FieldName::Hcl,
+        FieldName::Ecl,
+        FieldName::Pid,
+        FieldName::Cid,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            FieldName::Byr => "byr",
+            FieldName::Iyr => "iyr",
+            FieldName::Eyr => "eyr",
+            FieldName::Hgt => "hgt",
+            FieldName::Hcl => "hcl",
+            FieldName::Ecl => "ecl",
+            FieldName::Pid => "pid",
+            FieldName::Cid => "cid",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            FieldName::Byr => "birth year",
+            FieldName::Iyr => "issue year",
+            FieldName::Eyr => "expiration year",
+            FieldName::Hgt => "height",
+            FieldName::Hcl => "hair colour",
+            FieldName::Ecl => "eye colour",
+            FieldName::Pid => "passport ID",
+            FieldName::Cid => "country ID",
+        }
+    }
+}
+
+impl FromStr for FieldName {
+    type Err = ValidationError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        FieldName::ALL
+            .iter()
+            .find(|field| field.key() == string)
+            .copied()
+            .ok_or_else(|| ValidationError::UnknownField(string.to_string()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum YearError {
+    NotFourDigits(String),
+    OutOfRange { value: u16, min: u16, max: u16 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HeightError {
+    MissingUnit,
+    InvalidNumber(String),
+    OutOfRange {
+        value: u16,
+        min: u16,
+        max: u16,
+        unit: &'static str,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HairColourError {
+    MissingHash,
+    WrongLength(usize),
+    BadHexDigit(char),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EyeColourError {
+    Unknown(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PidError {
+    WrongLength(usize),
+    NotADigit(char),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    Missing(FieldName),
+    Duplicate(FieldName),
+    UnknownField(String),
+    MalformedField(String),
+    Byr(YearError),
+    Iyr(YearError),
+    Eyr(YearError),
+    Hgt(HeightError),
+    Hcl(HairColourError),
+    Ecl(EyeColourError),
+    Pid(PidError),
+}
+
+impl ValidationError {
+    pub fn field(&self) -> Option<FieldName> {
+        match self {
+            ValidationError::Missing(field) | ValidationError::Duplicate(field) => Some(*field),
+            ValidationError::UnknownField(_) | ValidationError::MalformedField(_) => None,
+            ValidationError::Byr(_) => Some(FieldName::Byr),
+            ValidationError::Iyr(_) => Some(FieldName::Iyr),
+            ValidationError::Eyr(_) => Some(FieldName::Eyr),
+            ValidationError::Hgt(_) => Some(FieldName::Hgt),
+            ValidationError::Hcl(_) => Some(FieldName::Hcl),
+            ValidationError::Ecl(_) => Some(FieldName::Ecl),
+            ValidationError::Pid(_) => Some(FieldName::Pid),
+        }
+    }
+}
+
+fn write_year(f: &mut Formatter, field: FieldName, error: &YearError) -> fmt::Result {
+    match error {
+        YearError::NotFourDigits(value) => write!(
+            f,
+            "The {} must be exactly 4 digits, found '{}'.",
+            field.description(),
+            value
+        ),
+        YearError::OutOfRange { value, min, max } => write!(
+            f,
+            "The {} must be between {} and {}, found {}.",
+            field.description(),
+            min,
+            max,
+            value
+        ),
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ValidationError::Missing(field) => {
+                write!(
+                    f,
+                    "The {} ({}) is missing.",
+                    field.description(),
+                    field.key()
+                )
+            }
+            ValidationError::Duplicate(field) => write!(
+                f,
+                "The {} ({}) appears more than once.",
+                field.description(),
+                field.key()
+            ),
+            ValidationError::UnknownField(key) => write!(f, "'{}' is not a known field.", key),
+            ValidationError::MalformedField(component) => {
+                write!(f, "'{}' should be of the form <key>:<value>.", component)
+            }
+            ValidationError::Byr(error) => write_year(f, FieldName::Byr, error),
+            ValidationError::Iyr(error) => write_year(f, FieldName::Iyr, error),
+            ValidationError::Eyr(error) => write_year(f, FieldName::Eyr, error),
+            ValidationError::Hgt(HeightError::MissingUnit) => {
+                write!(f, "The height must end with a unit of 'cm' or 'in'.")
+            }
+            ValidationError::Hgt(HeightError::InvalidNumber(value)) => {
+                write!(f, "The height must be a whole number, found '{}'.", value)
+            }
+            ValidationError::Hgt(HeightError::OutOfRange {
+                value,
+                min,
+                max,
+                unit,
+            }) => write!(
+                f,
+                "The height must be between {}{} and {}{}, found {}{}.",
+                min, unit, max, unit, value, unit
+            ),
+            ValidationError::Hcl(HairColourError::MissingHash) => {
+                write!(f, "The hair colour must start with '#'.")
+            }
+            ValidationError::Hcl(HairColourError::WrongLength(length)) => write!(
+                f,
+                "The hair colour must have 6 hex digits, found {}.",
+                length
+            ),
+            ValidationError::Hcl(HairColourError::BadHexDigit(c)) => {
+                write!(
+                    f,
+                    "The hair colour contains '{}', which isn't a hex digit.",
+                    c
+                )
+            }
+            ValidationError::Ecl(EyeColourError::Unknown(value)) => write!(
+                f,
+                "The eye colour must be one of amb, blu, brn, gry, grn, hzl or oth, found '{}'.",
+                value
+            ),
+            ValidationError::Pid(PidError::WrongLength(length)) => write!(
+                f,
+                "The passport ID must be exactly 9 digits, found {}.",
+                length
+            ),
+            ValidationError::Pid(PidError::NotADigit(c)) => {
+                write!(f, "The passport ID contains '{}', which isn't a digit.", c)
+            }
+        }
+    }
+}
+
+impl error::Error for ValidationError {}
+
+impl From<ValidationError> for io::Error {
+    fn from(error: ValidationError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    errors: Vec<ValidationError>,
+    decisions: Vec<Decision>,
+}
+
+impl ValidationReport {
+    pub fn push(&mut self, error: ValidationError) {
+        self.errors.push(error);
+    }
+
+    pub fn decide(&mut self, decision: Decision) {
+        self.decisions.push(decision);
+    }
+
+    pub fn decisions(&self) -> &[Decision] {
+        &self.decisions
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.decisions.iter().any(Decision::is_warning)
+    }
+
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn has_error_for(&self, field: FieldName) -> bool {
+        self.errors.iter().any(|error| error.field() == Some(field))
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "{}", error)?;
+        }
+        for decision in &self.decisions {
+            writeln!(f, "{}", decision)?;
+        }
+        Ok(())
+    }
+}
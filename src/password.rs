@@ -1,2 +1,8 @@
+pub mod audit;
+pub mod generator;
+pub mod inference;
 pub mod password;
 pub mod policy;
+pub mod position;
+pub mod schemes;
+pub mod stream;
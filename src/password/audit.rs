@@ -0,0 +1,424 @@
+use {
+    super::{
+        password::Password,
+        policy::{OccurrenceCount, PolicyError, PositionCheck, Scheme},
+    },
+    crate::io::csv,
+    std::{
+        collections::BTreeMap,
+        io::{Error, ErrorKind, Result, Write},
+        str::FromStr,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            "text" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unknown format '{}', expected text, csv or json", string),
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemeSummary {
+    name: &'static str,
+    valid: usize,
+    invalid_lines: Vec<usize>,
+    error_lines: Vec<(usize, PolicyError)>,
+}
+
+impl SchemeSummary {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn valid(&self) -> usize {
+        self.valid
+    }
+
+    pub fn invalid_lines(&self) -> &[usize] {
+        &self.invalid_lines
+    }
+
+    // lines where the policy couldn't be evaluated under the scheme, e.g. a position past the end.
+    pub fn error_lines(&self) -> &[(usize, PolicyError)] {
+        &self.error_lines
+    }
+
+    // whether the password was valid, recording the line if it wasn't.
+    fn record<S: Scheme>(&mut self, line_number: usize, password: &Password, scheme: &S) -> bool {
+        match password.is_valid(scheme) {
+            Ok(true) => {
+                self.valid += 1;
+                return true;
+            }
+            Ok(false) => self.invalid_lines.push(line_number),
+            Err(error) => self.error_lines.push((line_number, error)),
+        }
+        false
+    }
+}
+
+// line numbers start at 1, blank lines are skipped but still counted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Audit {
+    passwords: usize,
+    occurrence_count: SchemeSummary,
+    position_check: SchemeSummary,
+    only_occurrence_count: Vec<usize>,
+    only_position_check: Vec<usize>,
+    characters: BTreeMap<char, usize>,
+    policies: BTreeMap<String, usize>,
+    parse_failures: Vec<(usize, String)>,
+}
+
+impl Audit {
+    pub fn new(input: &str) -> Self {
+        let mut audit = Self {
+            passwords: 0,
+            occurrence_count: SchemeSummary::new("occurrence count"),
+            position_check: SchemeSummary::new("position check"),
+            only_occurrence_count: Vec::new(),
+            only_position_check: Vec::new(),
+            characters: BTreeMap::new(),
+            policies: BTreeMap::new(),
+            parse_failures: Vec::new(),
+        };
+        for (line_number, line) in (1..).zip(input.lines()) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match line.parse::<Password>() {
+                Ok(password) => audit.add(line_number, &password),
+                Err(error) => audit.parse_failures.push((line_number, error.to_string())),
+            }
+        }
+        audit
+    }
+
+    fn add(&mut self, line_number: usize, password: &Password) {
+        self.passwords += 1;
+        let occurrence_count =
+            self.occurrence_count
+                .record(line_number, password, &OccurrenceCount);
+        let position_check = self
+            .position_check
+            .record(line_number, password, &PositionCheck);
+        match (occurrence_count, position_check) {
+            (true, false) => self.only_occurrence_count.push(line_number),
+            (false, true) => self.only_position_check.push(line_number),
+            _ => {}
+        }
+        for c in password.text().chars() {
+            *self.characters.entry(c).or_default() += 1;
+        }
+        let policies = password.policies().iter().map(|policy| policy.to_string());
+        let policies = policies.collect::<Vec<_>>().join("; ");
+        *self.policies.entry(policies).or_default() += 1;
+    }
+
+    pub fn passwords(&self) -> usize {
+        self.passwords
+    }
+
+    pub fn schemes(&self) -> [&SchemeSummary; 2] {
+        [&self.occurrence_count, &self.position_check]
+    }
+
+    pub fn only_occurrence_count(&self) -> &[usize] {
+        &self.only_occurrence_count
+    }
+
+    pub fn only_position_check(&self) -> &[usize] {
+        &self.only_position_check
+    }
+
+    // how often each character appears across every password.
+    pub fn characters(&self) -> &BTreeMap<char, usize> {
+        &self.characters
+    }
+
+    pub fn policies(&self) -> &BTreeMap<String, usize> {
+        &self.policies
+    }
+
+    pub fn parse_failures(&self) -> &[(usize, String)] {
+        &self.parse_failures
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W, format: Format) -> Result<()> {
+        match format {
+            Format::Text => self.write_text(writer),
+            Format::Csv => self.write_csv(writer),
+            Format::Json => self.write_json(writer),
+        }
+    }
+
+    fn write_text<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let lines = |lines: &[usize]| {
+            if lines.is_empty() {
+                return "none".to_string();
+            }
+            let lines = lines.iter().map(usize::to_string).collect::<Vec<_>>();
+            lines.join(", ")
+        };
+        writeln!(writer, "Passwords = {}", self.passwords)?;
+        for scheme in self.schemes().iter() {
+            writeln!(writer, "Valid by {} = {}", scheme.name, scheme.valid)?;
+            writeln!(writer, "  Invalid lines = {}", lines(&scheme.invalid_lines))?;
+            for (line_number, error) in &scheme.error_lines {
+                writeln!(writer, "  Error on line {}, {}", line_number, error)?;
+            }
+        }
+        writeln!(
+            writer,
+            "Only valid by occurrence count = {}",
+            lines(&self.only_occurrence_count)
+        )?;
+        writeln!(
+            writer,
+            "Only valid by position check = {}",
+            lines(&self.only_position_check)
+        )?;
+        writeln!(writer, "Characters:")?;
+        for (c, count) in &self.characters {
+            writeln!(writer, "  {} -> {}", c, count)?;
+        }
+        writeln!(writer, "Policies:")?;
+        for (policy, count) in &self.policies {
+            writeln!(writer, "  {} -> {}", policy, count)?;
+        }
+        for (line_number, error) in &self.parse_failures {
+            writeln!(writer, "Failed to parse line {}, {}", line_number, error)?;
+        }
+        Ok(())
+    }
+
+    // one row per fact, as section,key,value.
+    fn write_csv<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut row = |section: &str, key: &str, value: &str| {
+            writeln!(
+                writer,
+                "{},{},{}",
+                csv::escape(section),
+                csv::escape(key),
+                csv::escape(value)
+            )
+        };
+        row("section", "key", "value")?;
+        row("summary", "passwords", &self.passwords.to_string())?;
+        for scheme in self.schemes().iter() {
+            row("valid", scheme.name, &scheme.valid.to_string())?;
+            for line_number in &scheme.invalid_lines {
+                row("invalid", scheme.name, &line_number.to_string())?;
+            }
+            for (line_number, error) in &scheme.error_lines {
+                let key = format!("{} line {}", scheme.name, line_number);
+                row("error", &key, &error.to_string())?;
+            }
+        }
+        for line_number in &self.only_occurrence_count {
+            row("only valid", "occurrence count", &line_number.to_string())?;
+        }
+        for line_number in &self.only_position_check {
+            row("only valid", "position check", &line_number.to_string())?;
+        }
+        for (c, count) in &self.characters {
+            row("character", &c.to_string(), &count.to_string())?;
+        }
+        for (policy, count) in &self.policies {
+            row("policy", policy, &count.to_string())?;
+        }
+        for (line_number, error) in &self.parse_failures {
+            row("parse failure", &line_number.to_string(), error)?;
+        }
+        Ok(())
+    }
+
+    fn write_json<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let lines = |lines: &[usize]| {
+            let lines = lines.iter().map(usize::to_string).collect::<Vec<_>>();
+            format!("[{}]", lines.join(", "))
+        };
+        let errors = |errors: Vec<(usize, String)>| {
+            let errors = errors.iter().map(|(line_number, error)| {
+                format!(
+                    "{{\"line\": {}, \"error\": {}}}",
+                    line_number,
+                    json_string(error)
+                )
+            });
+            format!("[{}]", errors.collect::<Vec<_>>().join(", "))
+        };
+        let counts = |counts: Vec<(String, usize)>| {
+            let counts = counts
+                .iter()
+                .map(|(key, count)| format!("{}: {}", json_string(key), count));
+            format!("{{{}}}", counts.collect::<Vec<_>>().join(", "))
+        };
+
+        writeln!(writer, "{{")?;
+        writeln!(writer, "  \"passwords\": {},", self.passwords)?;
+        writeln!(writer, "  \"schemes\": [")?;
+        for (index, scheme) in self.schemes().iter().enumerate() {
+            let scheme_errors = scheme
+                .error_lines
+                .iter()
+                .map(|(line_number, error)| (*line_number, error.to_string()));
+            writeln!(
+                writer,
+                "    {{\"name\": {}, \"valid\": {}, \"invalid_lines\": {}, \"errors\": {}}}{}",
+                json_string(scheme.name),
+                scheme.valid,
+                lines(&scheme.invalid_lines),
+                errors(scheme_errors.collect()),
+                if index == 0 { "," } else { "" }
+            )?;
+        }
+        writeln!(writer, "  ],")?;
+        writeln!(
+            writer,
+            "  \"only_occurrence_count\": {},",
+            lines(&self.only_occurrence_count)
+        )?;
+        writeln!(
+            writer,
+            "  \"only_position_check\": {},",
+            lines(&self.only_position_check)
+        )?;
+        let characters = self.characters.iter().map(|(c, n)| (c.to_string(), *n));
+        writeln!(
+            writer,
+            "  \"characters\": {},",
+            counts(characters.collect())
+        )?;
+        let policies = self.policies.iter().map(|(p, n)| (p.clone(), *n));
+        writeln!(writer, "  \"policies\": {},", counts(policies.collect()))?;
+        writeln!(
+            writer,
+            "  \"parse_failures\": {}",
+            errors(self.parse_failures.clone())
+        )?;
+        writeln!(writer, "}}")
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut string = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => string.push_str("\\\""),
+            '\\' => string.push_str("\\\\"),
+            '\n' => string.push_str("\\n"),
+            c if (c as u32) < 0x20 => string.push_str(&format!("\\u{:04x}", c as u32)),
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1-3 a: abcde
+1-3 b: cdefg
+
+2-9 c: ccccccccc
+1-9 d: dd
+oops";
+
+    fn output(audit: &Audit, format: Format) -> Result<String> {
+        let mut output = Vec::new();
+        audit.write(&mut output, format)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_audit() {
+        let audit = Audit::new(INPUT);
+        assert_eq!(audit.passwords(), 4);
+        let [occurrence_count, position_check] = audit.schemes();
+        assert_eq!(occurrence_count.valid(), 3);
+        assert_eq!(occurrence_count.invalid_lines(), [2]);
+        assert_eq!(position_check.valid(), 1);
+        assert_eq!(position_check.invalid_lines(), [2, 4]);
+        assert_eq!(
+            position_check.error_lines(),
+            [(
+                5,
+                PolicyError::PositionBeyondLength {
+                    position: 9,
+                    length: 2
+                }
+            )]
+        );
+        assert_eq!(audit.only_occurrence_count(), [4, 5]);
+        assert!(audit.only_position_check().is_empty());
+        assert_eq!(audit.characters()[&'c'], 11);
+        assert_eq!(audit.policies()["1-3 a"], 1);
+        assert_eq!(audit.parse_failures().len(), 1);
+        assert_eq!(audit.parse_failures()[0].0, 6);
+    }
+
+    #[test]
+    fn test_formats() -> Result<()> {
+        let audit = Audit::new("1-3 a: abcde\n1-3 b: cdefg");
+        assert_eq!(
+            output(&audit, Format::Text)?,
+            "Passwords = 2
+Valid by occurrence count = 1
+  Invalid lines = 2
+Valid by position check = 1
+  Invalid lines = 2
+Only valid by occurrence count = none
+Only valid by position check = none
+Characters:
+  a -> 1
+  b -> 1
+  c -> 2
+  d -> 2
+  e -> 2
+  f -> 1
+  g -> 1
+Policies:
+  1-3 a -> 1
+  1-3 b -> 1
+"
+        );
+        let csv = output(&audit, Format::Csv)?;
+        assert!(csv.starts_with("section,key,value\nsummary,passwords,2\nvalid,occurrence count,1\ninvalid,occurrence count,2\n"));
+        assert!(csv.ends_with("policy,1-3 a,1\npolicy,1-3 b,1\n"));
+
+        let audit = Audit::new("1-9 \": \"\"\nx");
+        let json = output(&audit, Format::Json)?;
+        assert!(json.contains("\"policies\": {\"1-9 \\\"\": 1},"));
+        assert!(json.contains("{\"name\": \"position check\", \"valid\": 0, \"invalid_lines\": [], \"errors\": [{\"line\": 1, \"error\": \"Policy position 9 is beyond the end of a password of length 2\"}]}"));
+        assert!(json.contains("\"parse_failures\": [{\"line\": 2, \"error\": "));
+        assert!("xml".parse::<Format>().is_err());
+        Ok(())
+    }
+}
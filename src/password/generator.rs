@@ -0,0 +1,308 @@
+use {
+    super::policy::{Policy, Scheme},
+    crate::num::random::Random,
+    std::io::{Error, ErrorKind, Result},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    TooFew,          // fewer of the policy's characters than the minimum.
+    TooMany,         // more of the policy's characters than the maximum.
+    NeitherPosition, // neither position holds one of the policy's characters.
+    BothPositions,   // both positions hold one of the policy's characters.
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Valid,
+    Invalid(Violation),
+}
+
+// policies can name any count or position, but a generated password is never longer than this.
+const MAX_LENGTH: usize = 1 << 16;
+
+fn impossible(policy: &Policy, reason: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Can't generate a password for '{}', {}", policy, reason),
+    )
+}
+
+// produces random passwords for a policy, seeded so that fixtures can be reproduced.
+pub struct Generator {
+    random: Random,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            random: Random::new(seed),
+        }
+    }
+
+    // printable ASCII plus anything named in the policy, split by whether the policy matches it.
+    fn alphabet(policy: &Policy) -> (Vec<char>, Vec<char>) {
+        let mut chars = ('!'..='~').collect::<Vec<_>>();
+        chars.extend(policy.to_string().chars().filter(|c| !c.is_ascii()));
+        chars.sort_unstable();
+        chars.dedup();
+        chars.into_iter().partition(|c| policy.matches(*c))
+    }
+
+    fn pick(&mut self, chars: &[char], count: usize) -> Vec<char> {
+        (0..count).map(|_| *self.random.choose(chars)).collect()
+    }
+
+    fn count_in(&mut self, min: usize, max: usize) -> usize {
+        min + self.random.below((max - min).saturating_add(1))
+    }
+
+    // a password for the occurrence count scheme, with a few unmatched characters mixed in.
+    pub fn occurrence_count(&mut self, policy: &Policy, outcome: Outcome) -> Result<String> {
+        let (matching, other) = Self::alphabet(policy);
+        if matching.is_empty() {
+            return Err(impossible(policy, "no characters match it"));
+        }
+        policy
+            .check_range()
+            .map_err(|error| impossible(policy, &error.to_string()))?;
+        let lower = policy.lower();
+        let too_long = || {
+            impossible(
+                policy,
+                &format!("it needs more than {} characters", MAX_LENGTH),
+            )
+        };
+        let count = match (outcome, policy.upper()) {
+            (Outcome::Valid, _) if lower > MAX_LENGTH => return Err(too_long()),
+            (Outcome::Valid, Some(upper)) => self.count_in(lower, upper.min(MAX_LENGTH)),
+            (Outcome::Valid, None) => self.count_in(lower, (lower + 3).min(MAX_LENGTH)),
+            (Outcome::Invalid(Violation::TooFew), _) if lower > 0 => {
+                self.count_in(0, (lower - 1).min(MAX_LENGTH))
+            }
+            (Outcome::Invalid(Violation::TooFew), _) => {
+                return Err(impossible(policy, "the minimum is 0"))
+            }
+            (Outcome::Invalid(Violation::TooMany), Some(upper)) if upper >= MAX_LENGTH => {
+                return Err(too_long())
+            }
+            (Outcome::Invalid(Violation::TooMany), Some(upper)) => {
+                self.count_in(upper + 1, (upper + 3).min(MAX_LENGTH))
+            }
+            (Outcome::Invalid(Violation::TooMany), None) => {
+                return Err(impossible(policy, "there's no maximum"))
+            }
+            (Outcome::Invalid(violation), _) => {
+                return Err(impossible(
+                    policy,
+                    &format!("{:?} doesn't apply to occurrence counts", violation),
+                ))
+            }
+        };
+        let mut password = self.pick(&matching, count);
+        if !other.is_empty() {
+            let fillers = self.count_in(if count == 0 { 1 } else { 0 }, 5);
+            password.extend(self.pick(&other, fillers));
+        } else if count == 0 {
+            return Err(impossible(policy, "the password would be empty"));
+        }
+        self.random.shuffle(&mut password);
+        Ok(password.into_iter().collect())
+    }
+
+    // a password for the position check scheme, a few characters longer than the upper position.
+    pub fn position_check(&mut self, policy: &Policy, outcome: Outcome) -> Result<String> {
+        let (matching, other) = Self::alphabet(policy);
+        let lower = policy.lower();
+        let upper = policy
+            .upper()
+            .ok_or_else(|| impossible(policy, "there's no upper position"))?;
+        if lower == 0 {
+            return Err(impossible(policy, "positions start at 1"));
+        }
+        if upper > MAX_LENGTH {
+            return Err(impossible(
+                policy,
+                &format!("positions beyond {} aren't generated", MAX_LENGTH),
+            ));
+        }
+        if matching.is_empty() || other.is_empty() {
+            return Err(impossible(policy, "it matches every character or none"));
+        }
+        let (first, second) = match outcome {
+            Outcome::Valid if lower == upper => {
+                return Err(impossible(policy, "both positions are the same"))
+            }
+            Outcome::Valid => {
+                let first = self.random.chance(1, 2);
+                (first, !first)
+            }
+            Outcome::Invalid(Violation::NeitherPosition) => (false, false),
+            Outcome::Invalid(Violation::BothPositions) => (true, true),
+            Outcome::Invalid(violation) => {
+                return Err(impossible(
+                    policy,
+                    &format!("{:?} doesn't apply to position checks", violation),
+                ))
+            }
+        };
+
+        let all = [matching.as_slice(), other.as_slice()].concat();
+        let length = upper + self.random.below(4);
+        let mut password = self.pick(&all, length);
+        for (position, matched) in [(lower, first), (upper, second)].iter() {
+            let chars = if *matched { &matching } else { &other };
+            password[position - 1] = *self.random.choose(chars);
+        }
+        Ok(password.into_iter().collect())
+    }
+
+    // for any other scheme, tries random passwords until one has the wanted validity. None if
+    // nothing was found within the attempts.
+    pub fn search<S: Scheme + ?Sized>(
+        &mut self,
+        policy: &Policy,
+        scheme: &S,
+        valid: bool,
+        attempts: usize,
+    ) -> Option<String> {
+        let (matching, other) = Self::alphabet(policy);
+        let longest = policy
+            .upper()
+            .unwrap_or(0)
+            .max(policy.lower())
+            .saturating_add(4)
+            .min(MAX_LENGTH);
+        for _ in 0..attempts {
+            let length = self.count_in(1, longest);
+            let password = (0..length)
+                .map(|_| {
+                    if other.is_empty() || (!matching.is_empty() && self.random.chance(1, 2)) {
+                        *self.random.choose(&matching)
+                    } else {
+                        *self.random.choose(&other)
+                    }
+                })
+                .collect::<String>();
+            if policy.is_valid(&password, scheme) == Ok(valid) {
+                return Some(password);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::password::{
+            policy::{OccurrenceCount, PositionCheck},
+            schemes::MinimumDistinct,
+        },
+    };
+
+    #[test]
+    fn test_occurrence_count() -> Result<()> {
+        let mut generator = Generator::new(2);
+        for policy in ["1-3 a", "0-2 digit", "2- [x-z]", "4-4 é"].iter() {
+            let policy = policy.parse::<Policy>()?;
+            for _ in 0..50 {
+                let password = generator.occurrence_count(&policy, Outcome::Valid)?;
+                assert_eq!(policy.is_valid(&password, &OccurrenceCount), Ok(true));
+            }
+        }
+        let policy = "2-3 b".parse::<Policy>()?;
+        for violation in [Violation::TooFew, Violation::TooMany].iter() {
+            for _ in 0..50 {
+                let password = generator.occurrence_count(&policy, Outcome::Invalid(*violation))?;
+                let count = password.chars().filter(|c| *c == 'b').count();
+                assert!(if *violation == Violation::TooFew {
+                    count < 2
+                } else {
+                    count > 3
+                });
+            }
+        }
+        let inverted = "3-2 b".parse::<Policy>()?;
+        assert!(generator
+            .occurrence_count(&inverted, Outcome::Valid)
+            .is_err());
+        let open = "0- b".parse::<Policy>()?;
+        assert!(generator
+            .occurrence_count(&open, Outcome::Invalid(Violation::TooFew))
+            .is_err());
+        assert!(generator
+            .occurrence_count(&open, Outcome::Invalid(Violation::TooMany))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_position_check() -> Result<()> {
+        let mut generator = Generator::new(3);
+        let policy = "2-5 lower".parse::<Policy>()?;
+        for _ in 0..50 {
+            let password = generator.position_check(&policy, Outcome::Valid)?;
+            assert_eq!(policy.is_valid(&password, &PositionCheck), Ok(true));
+            for violation in [Violation::NeitherPosition, Violation::BothPositions].iter() {
+                let password = generator.position_check(&policy, Outcome::Invalid(*violation))?;
+                assert_eq!(policy.is_valid(&password, &PositionCheck), Ok(false));
+                let both = policy.matches(password.chars().nth(1).unwrap());
+                assert_eq!(both, *violation == Violation::BothPositions);
+            }
+        }
+        let same = "3-3 a".parse::<Policy>()?;
+        assert!(generator.position_check(&same, Outcome::Valid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_large_and_open_bounds() -> Result<()> {
+        let mut generator = Generator::new(6);
+        let huge = format!("1-{} a", usize::MAX).parse::<Policy>()?;
+        for _ in 0..20 {
+            let password = generator.occurrence_count(&huge, Outcome::Valid)?;
+            assert!(password.chars().count() <= MAX_LENGTH + 5);
+            let password =
+                generator.occurrence_count(&huge, Outcome::Invalid(Violation::TooFew))?;
+            assert_eq!(password.chars().filter(|c| *c == 'a').count(), 0);
+        }
+        assert!(generator
+            .occurrence_count(&huge, Outcome::Invalid(Violation::TooMany))
+            .is_err());
+        assert!(generator.position_check(&huge, Outcome::Valid).is_err());
+        assert!(generator.search(&huge, &OccurrenceCount, true, 5).is_some());
+
+        let beyond = format!("{}- a", usize::MAX).parse::<Policy>()?;
+        assert!(generator.occurrence_count(&beyond, Outcome::Valid).is_err());
+        let password = generator.occurrence_count(&beyond, Outcome::Invalid(Violation::TooFew))?;
+        assert!(password.chars().count() <= MAX_LENGTH + 5);
+
+        let open = "5- a".parse::<Policy>()?;
+        for _ in 0..20 {
+            let password = generator.occurrence_count(&open, Outcome::Valid)?;
+            assert!(password.chars().count() <= 8 + 5);
+            assert_eq!(open.is_valid(&password, &OccurrenceCount), Ok(true));
+        }
+        assert!(generator
+            .search(&open, &OccurrenceCount, true, 100)
+            .is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_search() -> Result<()> {
+        let mut generator = Generator::new(4);
+        let policy = "1-3 a".parse::<Policy>()?;
+        let password = generator
+            .search(&policy, &MinimumDistinct(4), true, 100)
+            .unwrap();
+        assert_eq!(policy.is_valid(&password, &MinimumDistinct(4)), Ok(true));
+        assert_eq!(
+            generator.search(&policy, &MinimumDistinct(100), true, 100),
+            None
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    super::policy::{Policy, PositionCheck},
+    std::collections::BTreeSet,
+};
+
+fn candidate_chars(samples: &[(&str, bool)]) -> BTreeSet<char> {
+    samples
+        .iter()
+        .filter(|(_, valid)| *valid)
+        .flat_map(|(password, _)| password.chars())
+        .collect()
+}
+
+// the occurrence count policies consistent with the labelled passwords, tightest range first. each
+// policy's range is the smallest covering every valid password, so it only holds if no invalid
+// password falls inside it. empty if there's no valid password or nothing fits.
+pub fn infer_occurrence_count(samples: &[(&str, bool)]) -> Vec<Policy> {
+    let count = |password: &str, c: char| password.chars().filter(|x| *x == c).count();
+    let mut policies = Vec::new();
+    for c in candidate_chars(samples) {
+        let counts = samples
+            .iter()
+            .filter(|(_, valid)| *valid)
+            .map(|(password, _)| count(password, c));
+        let lower = counts.clone().min().unwrap();
+        let upper = counts.max().unwrap();
+        let consistent = samples
+            .iter()
+            .filter(|(_, valid)| !*valid)
+            .all(|(password, _)| !(lower..=upper).contains(&count(password, c)));
+        if consistent {
+            policies.push(Policy::new(lower, Some(upper), &[c]));
+        }
+    }
+    policies.sort_by_key(|policy| policy.upper().unwrap() - policy.lower());
+    policies
+}
+
+// the position check policies consistent with the labelled passwords, in order of position and
+// character. a password too short for the positions counts as invalid.
+pub fn infer_position_check(samples: &[(&str, bool)]) -> Vec<Policy> {
+    let shortest = samples
+        .iter()
+        .filter(|(_, valid)| *valid)
+        .map(|(password, _)| password.chars().count())
+        .min()
+        .unwrap_or(0);
+    let chars = candidate_chars(samples);
+    let mut policies = Vec::new();
+    for lower in 1..=shortest {
+        for upper in lower + 1..=shortest {
+            for c in chars.iter() {
+                let policy = Policy::new(lower, Some(upper), &[*c]);
+                let consistent = samples.iter().all(|(password, valid)| {
+                    policy.is_valid(password, &PositionCheck).unwrap_or(false) == *valid
+                });
+                if consistent {
+                    policies.push(policy);
+                }
+            }
+        }
+    }
+    policies
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::password::generator::{Generator, Outcome, Violation},
+        std::io::Result,
+    };
+
+    fn policies(policies: Vec<Policy>) -> Vec<String> {
+        policies.iter().map(Policy::to_string).collect()
+    }
+
+    #[test]
+    fn test_infer_occurrence_count() {
+        let samples = [("abcde", true), ("bdefg", false), ("ccccccccc", true)];
+        assert_eq!(policies(infer_occurrence_count(&samples)), ["1-9 c"]);
+        let samples = [
+            ("aab", true),
+            ("aaab", true),
+            ("b", false),
+            ("xaaaaa", false),
+        ];
+        assert_eq!(policies(infer_occurrence_count(&samples)), ["2-3 a"]);
+        assert!(infer_occurrence_count(&[("abc", false)]).is_empty());
+    }
+
+    #[test]
+    fn test_infer_position_check() {
+        let samples = [("abcde", true), ("cdefg", false), ("xbcdx", false)];
+        assert_eq!(
+            policies(infer_position_check(&samples)),
+            ["1-2 a", "1-3 a", "1-4 a", "1-5 a", "1-5 e", "2-5 e", "4-5 e"]
+        );
+    }
+
+    // enough passwords generated for a policy should lead back to it.
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let mut generator = Generator::new(5);
+        let policy = "2-4 q".parse::<Policy>()?;
+        let mut passwords = Vec::new();
+        for _ in 0..30 {
+            passwords.push((generator.occurrence_count(&policy, Outcome::Valid)?, true));
+            for violation in [Violation::TooFew, Violation::TooMany].iter() {
+                let password = generator.occurrence_count(&policy, Outcome::Invalid(*violation))?;
+                passwords.push((password, false));
+            }
+        }
+        let samples = passwords
+            .iter()
+            .map(|(password, valid)| (password.as_str(), *valid))
+            .collect::<Vec<_>>();
+        let inferred = infer_occurrence_count(&samples);
+        assert!(policies(inferred).contains(&"2-4 q".to_string()));
+        Ok(())
+    }
+}
@@ -1,18 +1,87 @@
 use {
-    super::policy::{Policy, Scheme},
+    super::policy::{parse_policies, Policy, PolicyError, Scheme},
     core::str::FromStr,
     std::io::{Error, ErrorKind, Result},
 };
 
 #[derive(Debug)]
 pub struct Password {
-    _policy: Policy,
+    _policies: Vec<Policy>,
     _password: String,
 }
 
+// a password line borrowed from its policies and text, so that lines can be checked without
+// copying them.
+#[derive(Clone, Copy, Debug)]
+pub struct PasswordRef<'a> {
+    policies: &'a [Policy],
+    password: &'a str,
+}
+
+impl<'a> PasswordRef<'a> {
+    pub fn new(policies: &'a [Policy], password: &'a str) -> Self {
+        Self { policies, password }
+    }
+
+    pub fn policies(&self) -> &'a [Policy] {
+        self.policies
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.password
+    }
+
+    // valid when every policy on the line is satisfied.
+    pub fn is_valid<S: Scheme + ?Sized>(
+        &self,
+        scheme: &S,
+    ) -> std::result::Result<bool, PolicyError> {
+        for policy in self.policies {
+            if !policy.is_valid(self.password, scheme)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
 impl Password {
-    pub fn is_valid(&self, scheme: Scheme) -> bool {
-        self._policy.is_valid(&self._password, scheme)
+    pub fn policies(&self) -> &[Policy] {
+        &self._policies
+    }
+
+    pub fn text(&self) -> &str {
+        &self._password
+    }
+
+    pub fn to_ref(&self) -> PasswordRef<'_> {
+        PasswordRef::new(&self._policies, &self._password)
+    }
+
+    pub fn is_valid<S: Scheme + ?Sized>(
+        &self,
+        scheme: &S,
+    ) -> std::result::Result<bool, PolicyError> {
+        self.to_ref().is_valid(scheme)
+    }
+}
+
+// splits a line into its unparsed policies and its password.
+pub fn split_line(string: &str) -> Result<(&str, &str)> {
+    let form = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            "input string should be of the form <policy>[; <policy>...]: <password>",
+        )
+    };
+    let delimiter_index = string.find(':').ok_or_else(form)?;
+    if delimiter_index < string.len() - 1 {
+        Ok((
+            &string[..delimiter_index],
+            string[delimiter_index + 1..].trim(),
+        ))
+    } else {
+        Err(form())
     }
 }
 
@@ -20,28 +89,26 @@ impl FromStr for Password {
     type Err = Error;
 
     fn from_str(string: &str) -> Result<Self> {
-        if let Some(delimiter_index) = string.find(':') {
-            let policy = string[..delimiter_index].parse();
-            if policy.is_ok() {
-                if delimiter_index < string.len() - 1 {
-                    Ok(Self {
-                        _policy: policy.unwrap(),
-                        _password: string[delimiter_index + 1..].trim().to_string(),
-                    })
-                } else {
-                    Err(Error::new(
-                        ErrorKind::InvalidData,
-                        "input string should be of the form <min>-<max> <char>: <password>",
-                    ))
-                }
-            } else {
-                Err(Error::new(ErrorKind::InvalidData, policy.unwrap_err()))
-            }
-        } else {
-            Err(Error::new(
-                ErrorKind::InvalidData,
-                "input string should be of the form <min>-<max> <char>: <password>",
-            ))
-        }
+        let (policies, password) = split_line(string)?;
+        Ok(Self {
+            _policies: parse_policies(policies)?,
+            _password: password.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::password::policy::OccurrenceCount};
+
+    #[test]
+    fn test_several_policies() -> Result<()> {
+        let password = "1-3 a; 1- digit: abc1".parse::<Password>()?;
+        assert_eq!(password.is_valid(&OccurrenceCount), Ok(true));
+        let password = "1-3 a; 1- digit: abc".parse::<Password>()?;
+        assert_eq!(password.is_valid(&OccurrenceCount), Ok(false));
+        assert!("1-3 a; : abc".parse::<Password>().is_err());
+        assert!("1-3 a abc".parse::<Password>().is_err());
+        Ok(())
     }
 }
@@ -1,92 +1,426 @@
-use std::{
-    io::{Error, ErrorKind, Result},
-    str::FromStr,
+use {
+    crate::{
+        pattern::{parse_char_class, CharClass},
+        text::grapheme::graphemes,
+    },
+    std::{
+        error,
+        fmt::{self, Display, Formatter},
+        io::{Error, ErrorKind, Result},
+        str::FromStr,
+    },
 };
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Policy {
-    _min_repetitions: u8,
-    _max_repetitions: u8,
-    _char: char,
+    _lower: usize,
+    _upper: Option<usize>, // None for an open ended range such as "3-".
+    _characters: CharClass,
+    _clusters: Vec<String>, // a literal policy's clusters of more than one char, such as "e\u{301}".
+    _characters_source: String,
 }
 
-pub enum Scheme {
-    OccurrenceCount,
-    PositionCheck,
+// an interpretation of a policy line, such as "1-3 a", against a password.
+pub trait Scheme {
+    fn is_valid(&self, policy: &Policy, password: &str) -> std::result::Result<bool, PolicyError>;
+}
+
+// the policy's characters must occur between lower and upper times in total.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OccurrenceCount;
+
+// one of the policy's characters must be at exactly one of the positions lower and upper.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PositionCheck;
+
+impl Scheme for OccurrenceCount {
+    fn is_valid(&self, policy: &Policy, password: &str) -> std::result::Result<bool, PolicyError> {
+        policy.check_range()?;
+        let count = policy.count(password);
+        Ok(count >= policy.lower() && policy.upper().is_none_or(|upper| count <= upper))
+    }
+}
+
+impl Scheme for PositionCheck {
+    fn is_valid(&self, policy: &Policy, password: &str) -> std::result::Result<bool, PolicyError> {
+        let first = policy.matches_at(password, policy.lower())?;
+        let second = policy.matches_at(password, policy.upper().ok_or(PolicyError::OpenEnded)?)?;
+        Ok(first != second)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolicyError {
+    PositionZero,
+    PositionBeyondLength { position: usize, length: usize },
+    OpenEnded, // the scheme needs a second position, but the policy has no upper bound.
+    InvertedRange { lower: usize, upper: usize }, // the scheme needs lower <= upper.
+}
+
+impl Display for PolicyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            PolicyError::PositionZero => write!(f, "Policy positions start at 1, found 0"),
+            PolicyError::PositionBeyondLength { position, length } => write!(
+                f,
+                "Policy position {} is beyond the end of a password of length {}",
+                position, length
+            ),
+            PolicyError::OpenEnded => write!(f, "Policy needs an upper bound for this scheme"),
+            PolicyError::InvertedRange { lower, upper } => write!(
+                f,
+                "Policy minimum {} is greater than the maximum {}",
+                lower, upper
+            ),
+        }
+    }
+}
+
+impl error::Error for PolicyError {}
+
+impl From<PolicyError> for Error {
+    fn from(error: PolicyError) -> Self {
+        Error::new(ErrorKind::InvalidData, error)
+    }
+}
+
+// positions are 1 based, counted in chars.
+pub fn char_at(password: &str, position: usize) -> std::result::Result<char, PolicyError> {
+    let index = position.checked_sub(1).ok_or(PolicyError::PositionZero)?;
+    password
+        .chars()
+        .nth(index)
+        .ok_or_else(|| PolicyError::PositionBeyondLength {
+            position,
+            length: password.chars().count(),
+        })
+}
+
+// literal characters are read as grapheme clusters. a cluster of several chars is matched as a
+// whole wherever its chars appear together, none of them match on their own.
+fn literal_characters(characters: &str) -> (CharClass, Vec<String>) {
+    let (single, clusters): (Vec<&str>, Vec<&str>) =
+        graphemes(characters).partition(|cluster| cluster.chars().nth(1).is_none());
+    let single = single
+        .iter()
+        .flat_map(|cluster| cluster.chars())
+        .collect::<Vec<_>>();
+    let clusters = clusters.into_iter().map(str::to_string).collect();
+    (CharClass::any_of(&single), clusters)
 }
 
 impl Policy {
-    pub fn is_valid(&self, password: &str, scheme: Scheme) -> bool {
-        match scheme {
-            Scheme::OccurrenceCount => {
-                let count = password.chars().filter(|c| c == &self._char).count() as u8;
-                count >= self._min_repetitions && count <= self._max_repetitions
-            }
-            Scheme::PositionCheck => {
-                let c1 = password
-                    .chars()
-                    .nth(self._min_repetitions as usize - 1)
-                    .unwrap();
-                let c2 = password
-                    .chars()
-                    .nth(self._max_repetitions as usize - 1)
-                    .unwrap();
-                (c1 == self._char && c2 != self._char) || (c1 != self._char && c2 == self._char)
+    // a policy matching any of the given chars, each on its own. unlike a parsed policy, chars
+    // which would form a grapheme cluster aren't joined into one.
+    pub fn new(lower: usize, upper: Option<usize>, characters: &[char]) -> Self {
+        Self {
+            _lower: lower,
+            _upper: upper,
+            _characters: CharClass::any_of(characters),
+            _clusters: Vec::new(),
+            _characters_source: characters.iter().collect(),
+        }
+    }
+
+    // the first number of the policy line, a count or a position depending on the scheme.
+    pub fn lower(&self) -> usize {
+        self._lower
+    }
+
+    pub fn upper(&self) -> Option<usize> {
+        self._upper
+    }
+
+    // positions may be given in either order, but a count or a range of positions can't be inverted.
+    pub fn check_range(&self) -> std::result::Result<(), PolicyError> {
+        match self._upper {
+            Some(upper) if upper < self._lower => Err(PolicyError::InvertedRange {
+                lower: self._lower,
+                upper,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn matches(&self, c: char) -> bool {
+        self._characters.matches(c)
+    }
+
+    // whether one of a literal policy's clusters of several chars starts the text.
+    pub fn cluster_at(&self, text: &[u8]) -> bool {
+        self._clusters
+            .iter()
+            .any(|cluster| text.starts_with(cluster.as_bytes()))
+    }
+
+    // whether the policy matches at the 1 based position, counted in chars. a cluster matches at
+    // the position of its first char.
+    pub fn matches_at(
+        &self,
+        password: &str,
+        position: usize,
+    ) -> std::result::Result<bool, PolicyError> {
+        let index = position.checked_sub(1).ok_or(PolicyError::PositionZero)?;
+        let (offset, c) = password.char_indices().nth(index).ok_or_else(|| {
+            PolicyError::PositionBeyondLength {
+                position,
+                length: password.chars().count(),
             }
+        })?;
+        Ok(self.matches(c) || self.cluster_at(&password.as_bytes()[offset..]))
+    }
+
+    // the number of the policy's characters in the password, a cluster counting once.
+    pub fn count(&self, password: &str) -> usize {
+        if self._clusters.is_empty() {
+            return password.chars().filter(|c| self.matches(*c)).count();
+        }
+        graphemes(password)
+            .map(|cluster| {
+                if self._clusters.iter().any(|literal| literal == cluster) {
+                    1
+                } else {
+                    cluster.chars().filter(|c| self.matches(*c)).count()
+                }
+            })
+            .sum()
+    }
+
+    // a grapheme cluster matches if it's a single matching char, or exactly one of a literal
+    // policy's own clusters, so that an accented letter written with a combining mark can be named
+    // in a policy.
+    pub fn matches_cluster(&self, cluster: &str) -> bool {
+        let mut chars = cluster.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return self.matches(c);
+        }
+        self._clusters.iter().any(|literal| literal == cluster)
+    }
+
+    pub fn is_valid<S: Scheme + ?Sized>(
+        &self,
+        password: &str,
+        scheme: &S,
+    ) -> std::result::Result<bool, PolicyError> {
+        scheme.is_valid(self, password)
+    }
+}
+
+impl Display for Policy {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}-", self._lower)?;
+        if let Some(upper) = self._upper {
+            write!(f, "{}", upper)?;
         }
+        write!(f, " {}", self._characters_source)
     }
 }
 
+const NAMED_CLASSES: [(&str, &str); 5] = [
+    ("digit", "[0-9]"),
+    ("lower", "[a-z]"),
+    ("upper", "[A-Z]"),
+    ("letter", "[a-zA-Z]"),
+    ("alnum", "[a-zA-Z0-9]"),
+];
+
+fn invalid(policy: &str, reason: String) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid policy '{}', {}", policy, reason),
+    )
+}
+
+// a policy is <min>-[<max>] <characters>, where the characters are one or more literal characters,
+// a class such as [a-f] or one of the names digit, lower, upper, letter or alnum.
 impl FromStr for Policy {
     type Err = Error;
 
-    fn from_str(mut string: &str) -> Result<Self> {
-        string = string.trim();
-        if let Some(delimiter_index) = string.find('-') {
-            let min_repetitions = string[..delimiter_index].trim().parse();
-            string = &string[delimiter_index + 1..].trim();
-            if min_repetitions.is_ok() {
-                let min_repetitions = min_repetitions.unwrap();
-                if let Some(delimiter_index) = string.find(' ') {
-                    let max_repetitions = string[..delimiter_index].trim().parse();
-                    string = &string[delimiter_index + 1..].trim();
-                    if max_repetitions.is_ok() {
-                        let max_repetitions = max_repetitions.unwrap();
-                        if string.len() == 1 {
-                            Ok(Self {
-                                _min_repetitions: min_repetitions,
-                                _max_repetitions: max_repetitions,
-                                _char: string.chars().next().unwrap(),
-                            })
-                        } else {
-                            Err(Error::new(
-                                ErrorKind::InvalidData,
-                                "Input string should be of the form <min>-<max> <char>",
-                            ))
-                        }
-                    } else {
-                        Err(Error::new(
-                            ErrorKind::InvalidData,
-                            max_repetitions.unwrap_err(),
-                        ))
-                    }
-                } else {
-                    Err(Error::new(
-                        ErrorKind::InvalidData,
-                        "Input string should be of the form <min>-<max> <char>",
-                    ))
-                }
-            } else {
+    fn from_str(string: &str) -> Result<Self> {
+        let policy = string.trim();
+        let (range, characters) = match policy.find(char::is_whitespace) {
+            Some(index) => (&policy[..index], policy[index..].trim()),
+            None if policy.contains('-') => {
+                return Err(invalid(
+                    policy,
+                    "expected characters after the range".into(),
+                ))
+            }
+            None => {
+                return Err(invalid(
+                    policy,
+                    "expected the form <min>-[<max>] <characters>".into(),
+                ))
+            }
+        };
+        let (lower, upper) = match range.find('-') {
+            Some(index) => (&range[..index], &range[index + 1..]),
+            None => {
+                return Err(invalid(
+                    policy,
+                    format!("expected a range such as 1-3 or 3-, found '{}'", range),
+                ))
+            }
+        };
+        let bound = |name: &str, bound: &str| {
+            bound.parse::<usize>().map_err(|_| {
+                invalid(
+                    policy,
+                    format!("the {} '{}' isn't a whole number", name, bound),
+                )
+            })
+        };
+        let lower = bound("minimum", lower)?;
+        let upper = match upper {
+            "" => None,
+            upper => Some(bound("maximum", upper)?),
+        };
+        let (class, clusters) = if characters.starts_with('[') {
+            let class =
+                parse_char_class(characters).map_err(|error| invalid(policy, error.to_string()))?;
+            (class, Vec::new())
+        } else if let Some((_, class)) = NAMED_CLASSES.iter().find(|(name, _)| *name == characters)
+        {
+            (parse_char_class(class).unwrap(), Vec::new())
+        } else if characters.contains(char::is_whitespace) {
+            return Err(invalid(
+                policy,
+                format!("unexpected whitespace in the characters '{}'", characters),
+            ));
+        } else {
+            literal_characters(characters)
+        };
+        Ok(Self {
+            _lower: lower,
+            _upper: upper,
+            _characters: class,
+            _clusters: clusters,
+            _characters_source: characters.to_string(),
+        })
+    }
+}
+
+// several policies may be given for one password, separated by ';'.
+pub fn parse_policies(string: &str) -> Result<Vec<Policy>> {
+    string
+        .split(';')
+        .map(|policy| {
+            if policy.trim().is_empty() {
                 Err(Error::new(
                     ErrorKind::InvalidData,
-                    min_repetitions.unwrap_err(),
+                    format!("Empty policy in '{}'", string.trim()),
                 ))
+            } else {
+                policy.parse()
             }
-        } else {
-            Err(Error::new(
-                ErrorKind::InvalidData,
-                "Input string should be of the form <min>-<max> <char>",
-            ))
-        }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_errors() -> Result<()> {
+        assert_eq!(
+            "1-3 a".parse::<Policy>()?.is_valid("abcde", &PositionCheck),
+            Ok(true)
+        );
+        assert_eq!(
+            "0-3 a".parse::<Policy>()?.is_valid("abcde", &PositionCheck),
+            Err(PolicyError::PositionZero)
+        );
+        assert_eq!(
+            "1-9 a".parse::<Policy>()?.is_valid("abcde", &PositionCheck),
+            Err(PolicyError::PositionBeyondLength {
+                position: 9,
+                length: 5
+            })
+        );
+        assert_eq!(
+            "0-9 a"
+                .parse::<Policy>()?
+                .is_valid("abcde", &OccurrenceCount),
+            Ok(true)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_grammar() -> Result<()> {
+        let policy = "1-3 ab".parse::<Policy>()?;
+        assert_eq!(policy.is_valid("xaybz", &OccurrenceCount), Ok(true));
+        assert_eq!(policy.is_valid("abab", &OccurrenceCount), Ok(false));
+        let policy = "2- [a-f]".parse::<Policy>()?;
+        assert_eq!(policy.upper(), None);
+        assert_eq!(policy.is_valid("xxxbcdefx", &OccurrenceCount), Ok(true));
+        assert_eq!(
+            policy.is_valid("abc", &PositionCheck),
+            Err(PolicyError::OpenEnded)
+        );
+        let policy = "1-2 digit".parse::<Policy>()?;
+        assert_eq!(policy.is_valid("a1b", &OccurrenceCount), Ok(true));
+        assert_eq!(policy.to_string(), "1-2 digit");
+        assert_eq!(parse_policies("1-3 a; 2- digit")?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_keeps_chars_apart() {
+        let policy = Policy::new(1, Some(2), &['e', '\u{301}']);
+        assert_eq!(policy.is_valid("exyz", &OccurrenceCount), Ok(true));
+        assert_eq!(policy.is_valid("e\u{301}", &OccurrenceCount), Ok(true));
+        assert_eq!(policy.is_valid("e\u{301}e", &OccurrenceCount), Ok(false));
+    }
+
+    // positions can be given in either order, as the original parser allowed, but counts can't.
+    #[test]
+    fn test_inverted_range() -> Result<()> {
+        let policy = "4-3 a".parse::<Policy>()?;
+        assert_eq!(policy.is_valid("abca", &PositionCheck), Ok(true));
+        assert_eq!(policy.is_valid("abaa", &PositionCheck), Ok(false));
+        assert_eq!(
+            policy.is_valid("abca", &OccurrenceCount),
+            Err(PolicyError::InvertedRange { lower: 4, upper: 3 })
+        );
+        assert_eq!(
+            PolicyError::InvertedRange { lower: 4, upper: 3 }.to_string(),
+            "Policy minimum 4 is greater than the maximum 3"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_grammar_errors() {
+        let error = |policy: &str| policy.parse::<Policy>().unwrap_err().to_string();
+        assert_eq!(
+            error("1-3"),
+            "Invalid policy '1-3', expected characters after the range"
+        );
+        assert_eq!(
+            error("13 a"),
+            "Invalid policy '13 a', expected a range such as 1-3 or 3-, found '13'"
+        );
+        assert_eq!(
+            error("x-3 a"),
+            "Invalid policy 'x-3 a', the minimum 'x' isn't a whole number"
+        );
+        assert_eq!(
+            error("1-y a"),
+            "Invalid policy '1-y a', the maximum 'y' isn't a whole number"
+        );
+        assert_eq!(
+            error("1-3 [a-f"),
+            "Invalid policy '1-3 [a-f', Invalid pattern, unterminated character class"
+        );
+        assert_eq!(
+            error("1-3 a b"),
+            "Invalid policy '1-3 a b', unexpected whitespace in the characters 'a b'"
+        );
+        assert_eq!(
+            parse_policies("1-3 a;").unwrap_err().to_string(),
+            "Empty policy in '1-3 a;'"
+        );
     }
 }
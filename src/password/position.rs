@@ -0,0 +1,144 @@
+use {
+    super::policy::{Policy, PolicyError, Scheme},
+    crate::text::grapheme::graphemes,
+};
+
+// what a policy position counts. scalar values are what PositionCheck has always used, graphemes
+// are what a person reading the password would count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionUnit {
+    Byte,
+    #[default]
+    Scalar,
+    Grapheme,
+}
+
+impl PositionUnit {
+    pub fn len(self, password: &str) -> usize {
+        match self {
+            PositionUnit::Byte => password.len(),
+            PositionUnit::Scalar => password.chars().count(),
+            PositionUnit::Grapheme => graphemes(password).count(),
+        }
+    }
+
+    // whether the unit at the 1 based position matches the policy. a byte only matches if it's a
+    // whole ASCII character, never part of a longer encoding. in bytes and scalars, a cluster of
+    // several chars matches where its encoding starts.
+    pub fn matches_at(
+        self,
+        policy: &Policy,
+        password: &str,
+        position: usize,
+    ) -> Result<bool, PolicyError> {
+        let index = position.checked_sub(1).ok_or(PolicyError::PositionZero)?;
+        let beyond = || PolicyError::PositionBeyondLength {
+            position,
+            length: self.len(password),
+        };
+        match self {
+            PositionUnit::Byte => {
+                let byte = *password.as_bytes().get(index).ok_or_else(beyond)?;
+                Ok((byte.is_ascii() && policy.matches(char::from(byte)))
+                    || policy.cluster_at(&password.as_bytes()[index..]))
+            }
+            PositionUnit::Scalar => policy.matches_at(password, position),
+            PositionUnit::Grapheme => {
+                Ok(policy.matches_cluster(graphemes(password).nth(index).ok_or_else(beyond)?))
+            }
+        }
+    }
+}
+
+// PositionCheck, counting positions in the given unit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionCheckIn(pub PositionUnit);
+
+impl Scheme for PositionCheckIn {
+    fn is_valid(&self, policy: &Policy, password: &str) -> Result<bool, PolicyError> {
+        let upper = policy.upper().ok_or(PolicyError::OpenEnded)?;
+        let first = self.0.matches_at(policy, password, policy.lower())?;
+        let second = self.0.matches_at(policy, password, upper)?;
+        Ok(first != second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::password::policy::{OccurrenceCount, PositionCheck},
+        std::io::Result,
+    };
+
+    #[test]
+    fn test_units() -> Result<()> {
+        // "café" with the accent as a combining mark, then "ok".
+        let password = "cafe\u{301}ok";
+        let policy = "4-5 o".parse::<Policy>()?;
+        let check = |unit| policy.is_valid(password, &PositionCheckIn(unit));
+        assert_eq!(check(PositionUnit::Byte), Ok(false));
+        assert_eq!(check(PositionUnit::Scalar), Ok(false));
+        assert_eq!(check(PositionUnit::Grapheme), Ok(true));
+        assert_eq!(PositionUnit::Byte.len(password), 8);
+        assert_eq!(PositionUnit::Scalar.len(password), 7);
+        assert_eq!(PositionUnit::Grapheme.len(password), 6);
+        assert_eq!(
+            "1-7 o"
+                .parse::<Policy>()?
+                .is_valid(password, &PositionCheckIn(PositionUnit::Grapheme)),
+            Err(PolicyError::PositionBeyondLength {
+                position: 7,
+                length: 6
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_clusters_in_policies() -> Result<()> {
+        let graphemes = PositionCheckIn(PositionUnit::Grapheme);
+        let policy = "1-4 e\u{301}".parse::<Policy>()?;
+        assert_eq!(policy.is_valid("e\u{301}xyz", &graphemes), Ok(true));
+        assert_eq!(policy.is_valid("e\u{301}xye\u{301}", &graphemes), Ok(false));
+        assert_eq!(policy.is_valid("exyz", &graphemes), Ok(false));
+        assert_eq!(policy.is_valid("\u{301}xyz", &graphemes), Ok(false));
+        assert_eq!(policy.is_valid("exye\u{301}", &graphemes), Ok(true));
+        // in bytes and scalars the cluster matches where it starts, and it counts once.
+        for unit in [PositionUnit::Byte, PositionUnit::Scalar].iter() {
+            let check = PositionCheckIn(*unit);
+            assert_eq!(policy.is_valid("e\u{301}xyz", &check), Ok(true));
+            assert_eq!(policy.is_valid("exyz", &check), Ok(false));
+        }
+        assert_eq!(policy.is_valid("e\u{301}xyz", &PositionCheck), Ok(true));
+        assert_eq!(policy.is_valid("e\u{301}", &OccurrenceCount), Ok(true));
+        assert_eq!(
+            policy.is_valid("ee\u{301}e\u{301}", &OccurrenceCount),
+            Ok(true)
+        );
+        assert_eq!(
+            policy.is_valid("e\u{301}u\u{301}", &OccurrenceCount),
+            Ok(true)
+        );
+        assert_eq!(policy.is_valid("exyz\u{301}", &OccurrenceCount), Ok(false));
+        let policy = "2-3 \u{1f44d}\u{1f3fd}".parse::<Policy>()?;
+        assert_eq!(
+            policy.is_valid("a\u{1f44d}\u{1f3fd}b", &graphemes),
+            Ok(true)
+        );
+        assert_eq!(policy.is_valid("a\u{1f44d}b", &graphemes), Ok(false));
+        assert_eq!(
+            policy.is_valid("ab\u{1f44d}\u{1f3fc}", &graphemes),
+            Ok(false)
+        );
+
+        let policy = "1-3 a".parse::<Policy>()?;
+        for password in ["abcde", "cdefg", "ccccccccc"].iter() {
+            assert_eq!(
+                policy.is_valid(password, &PositionCheckIn::default()),
+                policy.is_valid(password, &PositionCheck)
+            );
+        }
+        Ok(())
+    }
+}
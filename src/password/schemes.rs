@@ -0,0 +1,145 @@
+use super::policy::{Policy, PolicyError, Scheme};
+
+// one of the policy's characters must be at exactly one of the given positions, the policy's numbers are
+// ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExactlyOneOf {
+    pub positions: Vec<usize>,
+}
+
+impl Scheme for ExactlyOneOf {
+    fn is_valid(&self, policy: &Policy, password: &str) -> Result<bool, PolicyError> {
+        let mut matches = 0;
+        for position in &self.positions {
+            if policy.matches_at(password, *position)? {
+                matches += 1;
+            }
+        }
+        Ok(matches == 1)
+    }
+}
+
+// the policy's characters mustn't appear anywhere between positions lower and upper inclusive, an
+// open ended range continues to the end of the password.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForbiddenInRange;
+
+impl Scheme for ForbiddenInRange {
+    fn is_valid(&self, policy: &Policy, password: &str) -> Result<bool, PolicyError> {
+        policy.check_range()?;
+        let upper = policy.upper().unwrap_or_else(|| password.chars().count());
+        for position in policy.lower()..=upper {
+            if policy.matches_at(password, position)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+// the password must contain at least this many different characters, whatever the policy says.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinimumDistinct(pub usize);
+
+impl Scheme for MinimumDistinct {
+    fn is_valid(&self, _: &Policy, password: &str) -> Result<bool, PolicyError> {
+        let mut seen = password.chars().collect::<Vec<_>>();
+        seen.sort_unstable();
+        seen.dedup();
+        Ok(seen.len() >= self.0)
+    }
+}
+
+// every scheme must pass, an error from any scheme is returned.
+pub struct All(pub Vec<Box<dyn Scheme>>);
+
+impl Scheme for All {
+    fn is_valid(&self, policy: &Policy, password: &str) -> Result<bool, PolicyError> {
+        for scheme in &self.0 {
+            if !scheme.is_valid(policy, password)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+// at least one scheme must pass, an error from any scheme is returned.
+pub struct Any(pub Vec<Box<dyn Scheme>>);
+
+impl Scheme for Any {
+    fn is_valid(&self, policy: &Policy, password: &str) -> Result<bool, PolicyError> {
+        for scheme in &self.0 {
+            if scheme.is_valid(policy, password)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::password::{
+            password::Password,
+            policy::{OccurrenceCount, PositionCheck},
+        },
+        std::io::Result,
+    };
+
+    #[test]
+    fn test_custom_schemes() -> Result<()> {
+        let password = "1-3 a: abcde".parse::<Password>()?;
+        assert_eq!(password.is_valid(&OccurrenceCount), Ok(true));
+        assert_eq!(password.is_valid(&PositionCheck), Ok(true));
+        let scheme = ExactlyOneOf {
+            positions: vec![1, 3, 5],
+        };
+        assert_eq!(password.is_valid(&scheme), Ok(true));
+        assert_eq!(password.is_valid(&ForbiddenInRange), Ok(false));
+        assert_eq!(
+            "2-4 a: abcde"
+                .parse::<Password>()?
+                .is_valid(&ForbiddenInRange),
+            Ok(true)
+        );
+        assert_eq!(
+            "4-2 a: abcde"
+                .parse::<Password>()?
+                .is_valid(&ForbiddenInRange),
+            Err(PolicyError::InvertedRange { lower: 4, upper: 2 })
+        );
+        assert_eq!(password.is_valid(&MinimumDistinct(5)), Ok(true));
+        assert_eq!(password.is_valid(&MinimumDistinct(6)), Ok(false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_composite_schemes() -> Result<()> {
+        let password = "1-3 b: cdefg".parse::<Password>()?;
+        let all = All(vec![
+            Box::new(OccurrenceCount),
+            Box::new(MinimumDistinct(3)),
+        ]);
+        assert_eq!(password.is_valid(&all), Ok(false));
+        let any = Any(vec![
+            Box::new(OccurrenceCount),
+            Box::new(MinimumDistinct(3)),
+        ]);
+        assert_eq!(password.is_valid(&any), Ok(true));
+        let any = Any(vec![
+            Box::new(PositionCheck),
+            Box::new(ExactlyOneOf { positions: vec![9] }),
+        ]);
+        assert_eq!(
+            password.is_valid(&any),
+            Err(PolicyError::PositionBeyondLength {
+                position: 9,
+                length: 5
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use {
+    super::{
+        password::{split_line, PasswordRef},
+        policy::{parse_policies, Policy, Scheme},
+    },
+    std::{
+        collections::HashMap,
+        fmt::{self, Display, Formatter},
+        io::{BufRead, Error, ErrorKind, Result},
+        str,
+        time::{Duration, Instant},
+    },
+};
+
+// dumps usually repeat a small set of policies, the cache is cleared if a file has more than this
+// so that memory stays bounded.
+const POLICY_CACHE_LIMIT: usize = 4096;
+
+#[derive(Debug)]
+pub struct StreamSummary {
+    lines: usize,
+    bytes: usize,
+    malformed: usize,
+    valid: Vec<usize>,
+    errors: Vec<usize>,
+    first_error: Option<Error>,
+    elapsed: Duration,
+}
+
+impl StreamSummary {
+    // every line read, including blank and malformed ones.
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn malformed(&self) -> usize {
+        self.malformed
+    }
+
+    // the number of valid passwords for each scheme, in the order they were given.
+    pub fn valid(&self) -> &[usize] {
+        &self.valid
+    }
+
+    // the number of passwords a scheme couldn't evaluate, these aren't counted as valid.
+    pub fn errors(&self) -> &[usize] {
+        &self.errors
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn lines_per_second(&self) -> f64 {
+        self.lines as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn megabytes_per_second(&self) -> f64 {
+        self.bytes as f64 / 1e6 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    // the summary, or the first malformed line's error if there was one.
+    pub fn strict(self) -> Result<Self> {
+        match self.first_error {
+            Some(error) => Err(error),
+            None => Ok(self),
+        }
+    }
+}
+
+impl Display for StreamSummary {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} lines, {:.1} MB in {:.3}s ({:.0} lines/s, {:.1} MB/s)",
+            self.lines,
+            self.bytes as f64 / 1e6,
+            self.elapsed.as_secs_f64(),
+            self.lines_per_second(),
+            self.megabytes_per_second()
+        )
+    }
+}
+
+fn cached_policies<'c>(
+    cache: &'c mut HashMap<String, Vec<Policy>>,
+    policies: &str,
+) -> Result<&'c [Policy]> {
+    if !cache.contains_key(policies) {
+        if cache.len() >= POLICY_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(policies.to_string(), parse_policies(policies)?);
+    }
+    Ok(&cache[policies])
+}
+
+// checks password lines as they're read, reusing one line buffer and parsing each distinct policy
+// once, so that nothing is allocated per line and the whole input is never held in memory.
+pub struct StreamValidator<'s> {
+    schemes: Vec<&'s dyn Scheme>,
+    policies: HashMap<String, Vec<Policy>>,
+}
+
+impl<'s> StreamValidator<'s> {
+    pub fn new(schemes: Vec<&'s dyn Scheme>) -> Self {
+        Self {
+            schemes,
+            policies: HashMap::new(),
+        }
+    }
+
+    pub fn validate<R: BufRead>(&mut self, mut reader: R) -> Result<StreamSummary> {
+        let start = Instant::now();
+        let mut summary = StreamSummary {
+            lines: 0,
+            bytes: 0,
+            malformed: 0,
+            valid: vec![0; self.schemes.len()],
+            errors: vec![0; self.schemes.len()],
+            first_error: None,
+            elapsed: Duration::default(),
+        };
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            let read = reader.read_until(b'\n', &mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            summary.lines += 1;
+            summary.bytes += read;
+            match self.check_line(&buffer, &mut summary.valid, &mut summary.errors) {
+                Ok(()) => {}
+                Err(error) => {
+                    summary.malformed += 1;
+                    if summary.first_error.is_none() {
+                        summary.first_error = Some(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("line {}: {}", summary.lines, error),
+                        ));
+                    }
+                }
+            }
+        }
+        summary.elapsed = start.elapsed();
+        Ok(summary)
+    }
+
+    fn check_line(&mut self, line: &[u8], valid: &mut [usize], errors: &mut [usize]) -> Result<()> {
+        let line = str::from_utf8(line)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))?
+            .trim_end_matches(['\n', '\r']);
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let (policies, password) = split_line(line)?;
+        let policies = cached_policies(&mut self.policies, policies)?;
+        let password = PasswordRef::new(policies, password);
+        for (index, scheme) in self.schemes.iter().enumerate() {
+            match password.is_valid(*scheme) {
+                Ok(true) => valid[index] += 1,
+                Ok(false) => {}
+                Err(_) => errors[index] += 1,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::password::policy::{OccurrenceCount, PositionCheck},
+    };
+
+    #[test]
+    fn test_stream() -> Result<()> {
+        let input = "1-3 a: abcde\r\n1-3 b: cdefg\n\n2-9 c: ccccccccc\n1-9 d: dd\n";
+        let mut validator = StreamValidator::new(vec![&OccurrenceCount, &PositionCheck]);
+        let summary = validator.validate(input.as_bytes())?.strict()?;
+        assert_eq!(summary.lines(), 5);
+        assert_eq!(summary.bytes(), input.len());
+        assert_eq!(summary.valid(), [3, 1]);
+        assert_eq!(summary.errors(), [0, 1]);
+        assert_eq!(summary.malformed(), 0);
+
+        let input = b"1-3 a: abcde\n1-3 a abcde\n\xff: a\n1-3 a: a";
+        let summary = validator.validate(&input[..])?;
+        assert_eq!(summary.valid(), [2, 1]);
+        assert_eq!(summary.errors(), [0, 1]);
+        assert_eq!(summary.malformed(), 2);
+        assert_eq!(
+            summary.strict().unwrap_err().to_string(),
+            "line 2: input string should be of the form <policy>[; <policy>...]: <password>"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_policy_cache_is_bounded() -> Result<()> {
+        let input = (0..POLICY_CACHE_LIMIT + 10)
+            .map(|upper| format!("0-{} a: a\n", upper))
+            .collect::<String>();
+        let mut validator = StreamValidator::new(vec![&OccurrenceCount]);
+        let summary = validator.validate(input.as_bytes())?.strict()?;
+        assert_eq!(summary.valid(), [POLICY_CACHE_LIMIT + 9]);
+        assert!(validator.policies.len() <= POLICY_CACHE_LIMIT);
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{Error, ErrorKind, Result},
+    str::FromStr,
+};
+
+// A small regular expression subset which always matches the whole input: literals, '.', escapes
+// (\d, \w, \s or an escaped literal), character classes with ranges and negation, and the
+// quantifiers ?, *, + and {n}, {n,}, {n,m}. Groups and alternation aren't supported.
+
+#[derive(Clone, Debug, PartialEq)]
+enum ClassItem {
+    Char(char),
+    Range(char, char),
+    Digit,
+    Word,
+    Space,
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match *self {
+            ClassItem::Char(expected) => c == expected,
+            ClassItem::Range(low, high) => (low..=high).contains(&c),
+            ClassItem::Digit => c.is_ascii_digit(),
+            ClassItem::Word => c.is_alphanumeric() || c == '_',
+            ClassItem::Space => c.is_whitespace(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharClass {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+impl CharClass {
+    pub fn any_of(chars: &[char]) -> Self {
+        Self {
+            negated: false,
+            items: chars.iter().map(|c| ClassItem::Char(*c)).collect(),
+        }
+    }
+
+    pub fn matches(&self, c: char) -> bool {
+        self.items.iter().any(|item| item.matches(c)) != self.negated
+    }
+}
+
+// parses the inside of a [...] class, the opening bracket having already been consumed.
+fn parse_class(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<CharClass> {
+    let mut negated = false;
+    if chars.peek() == Some(&'^') {
+        chars.next();
+        negated = true;
+    }
+    let mut items = Vec::new();
+    loop {
+        let item = match chars.next() {
+            None => return Err(invalid("unterminated character class")),
+            Some(']') if !items.is_empty() => break,
+            Some('\\') => parse_escape(chars)?,
+            Some(c) => ClassItem::Char(c),
+        };
+        let mut lookahead = chars.clone();
+        match (item, lookahead.next(), lookahead.next()) {
+            (ClassItem::Char(low), Some('-'), Some(high)) if high != ']' => {
+                if high < low {
+                    return Err(invalid("character range is out of order"));
+                }
+                chars.next();
+                chars.next();
+                items.push(ClassItem::Range(low, high));
+            }
+            (item, _, _) => items.push(item),
+        }
+    }
+    Ok(CharClass { negated, items })
+}
+
+fn parse_escape(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<ClassItem> {
+    match chars.next() {
+        Some('d') => Ok(ClassItem::Digit),
+        Some('w') => Ok(ClassItem::Word),
+        Some('s') => Ok(ClassItem::Space),
+        Some(c) => Ok(ClassItem::Char(c)),
+        None => Err(invalid("pattern ends with an escape")),
+    }
+}
+
+pub fn parse_char_class(string: &str) -> Result<CharClass> {
+    let mut chars = string.chars().peekable();
+    if chars.next() != Some('[') {
+        return Err(invalid("character class should start with '['"));
+    }
+    let class = parse_class(&mut chars)?;
+    if chars.next().is_some() {
+        return Err(invalid("unexpected characters after character class"));
+    }
+    Ok(class)
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid pattern, {}", reason),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Atom {
+    Any,
+    Class(CharClass),
+}
+
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Any => true,
+            Atom::Class(class) => class.matches(c),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Piece {
+    atom: Atom,
+    min: usize,
+    max: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+impl Pattern {
+    pub fn matches(&self, input: &str) -> bool {
+        let chars = input.chars().collect::<Vec<_>>();
+        Self::match_from(&self.pieces, &chars)
+    }
+
+    // greedy backtracking, trying the longest repetition of each piece first.
+    fn match_from(pieces: &[Piece], chars: &[char]) -> bool {
+        match pieces.split_first() {
+            None => chars.is_empty(),
+            Some((piece, rest)) => {
+                let max = piece.max.unwrap_or(chars.len()).min(chars.len());
+                let available = chars[..max]
+                    .iter()
+                    .take_while(|c| piece.atom.matches(**c))
+                    .count();
+                (piece.min..=available)
+                    .rev()
+                    .any(|count| Self::match_from(rest, &chars[count..]))
+            }
+        }
+    }
+}
+
+fn parse_count(digits: &str) -> Result<usize> {
+    digits
+        .parse()
+        .map_err(|_| invalid("repetition count should be a number"))
+}
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        let body = string.strip_prefix('^').unwrap_or(string);
+        let body = body.strip_suffix('$').unwrap_or(body);
+        let mut chars = body.chars().peekable();
+        let mut pieces: Vec<Piece> = Vec::new();
+
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '.' => Atom::Any,
+                '[' => Atom::Class(parse_class(&mut chars)?),
+                '\\' => Atom::Class(CharClass {
+                    negated: false,
+                    items: vec![parse_escape(&mut chars)?],
+                }),
+                '(' | ')' | '|' => return Err(invalid("groups and alternation aren't supported")),
+                '?' | '*' | '+' | '{' => {
+                    let piece = pieces
+                        .last_mut()
+                        .ok_or_else(|| invalid("quantifier without anything to repeat"))?;
+                    if piece.min != 1 || piece.max != Some(1) {
+                        return Err(invalid("repeated quantifier"));
+                    }
+                    let (min, max) = match c {
+                        '?' => (0, Some(1)),
+                        '*' => (0, None),
+                        '+' => (1, None),
+                        _ => {
+                            let mut bounds = String::new();
+                            loop {
+                                match chars.next() {
+                                    Some('}') => break,
+                                    Some(c) => bounds.push(c),
+                                    None => return Err(invalid("unterminated repetition")),
+                                }
+                            }
+                            match bounds.find(',') {
+                                None => {
+                                    let count = parse_count(&bounds)?;
+                                    (count, Some(count))
+                                }
+                                Some(index) if index == bounds.len() - 1 => {
+                                    (parse_count(&bounds[..index])?, None)
+                                }
+                                Some(index) => (
+                                    parse_count(&bounds[..index])?,
+                                    Some(parse_count(&bounds[index + 1..])?),
+                                ),
+                            }
+                        }
+                    };
+                    if max.is_some_and(|max| max < min) {
+                        return Err(invalid("repetition bounds are out of order"));
+                    }
+                    piece.min = min;
+                    piece.max = max;
+                    continue;
+                }
+                c => Atom::Class(CharClass {
+                    negated: false,
+                    items: vec![ClassItem::Char(c)],
+                }),
+            };
+            pieces.push(Piece {
+                atom,
+                min: 1,
+                max: Some(1),
+            });
+        }
+
+        Ok(Self {
+            source: string.to_string(),
+            pieces,
+        })
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literals_and_classes() -> Result<()> {
+        let pattern = "#[0-9a-f]{6}".parse::<Pattern>()?;
+        assert!(pattern.matches("#123abc"));
+        assert!(!pattern.matches("#123abz"));
+        assert!(!pattern.matches("#123abcd"));
+        assert!(!pattern.matches("123abc"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_quantifiers() -> Result<()> {
+        let pattern = r"\d{3,}(cm|in)".parse::<Pattern>();
+        assert!(pattern.is_err());
+        let pattern = r"^\d+c?m$".parse::<Pattern>()?;
+        assert!(pattern.matches("183cm"));
+        assert!(pattern.matches("183m"));
+        assert!(!pattern.matches("cm"));
+        let pattern = "a.*b[^x]".parse::<Pattern>()?;
+        assert!(pattern.matches("ab_"));
+        assert!(pattern.matches("azzzbbby"));
+        assert!(!pattern.matches("azzzbx"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_char_class() -> Result<()> {
+        let class = parse_char_class("[a-f]")?;
+        assert!(class.matches('c'));
+        assert!(!class.matches('g'));
+        assert!(parse_char_class("[a-f").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use std::{cmp::Ordering, iter::FusedIterator};
+
+// the grapheme cluster break properties of UAX #29 that the rules below need.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Category {
+    Cr,
+    Lf,
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    SpacingMark,
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+    Pictographic,
+    Other,
+}
+
+// the Unicode tables are large, these ranges cover the combining marks of the common European,
+// Hebrew, Arabic, Indic and Thai scripts, Hangul, and emoji sequences.
+const EXTEND: &[(u32, u32)] = &[
+    (0x0300, 0x036f),
+    (0x0483, 0x0489),
+    (0x0591, 0x05bd),
+    (0x05bf, 0x05bf),
+    (0x05c1, 0x05c2),
+    (0x05c4, 0x05c5),
+    (0x05c7, 0x05c7),
+    (0x0610, 0x061a),
+    (0x064b, 0x065f),
+    (0x0670, 0x0670),
+    (0x06d6, 0x06dc),
+    (0x06df, 0x06e4),
+    (0x06e7, 0x06e8),
+    (0x06ea, 0x06ed),
+    (0x0900, 0x0902),
+    (0x093a, 0x093a),
+    (0x093c, 0x093c),
+    (0x0941, 0x0948),
+    (0x094d, 0x094d),
+    (0x0951, 0x0957),
+    (0x0962, 0x0963),
+    (0x0e31, 0x0e31),
+    (0x0e34, 0x0e3a),
+    (0x0e47, 0x0e4e),
+    (0x1ab0, 0x1aff),
+    (0x1dc0, 0x1dff),
+    (0x200c, 0x200c),
+    (0x20d0, 0x20ff),
+    (0x302a, 0x302f),
+    (0x3099, 0x309a),
+    (0xfe00, 0xfe0f),
+    (0xfe20, 0xfe2f),
+    (0x1f3fb, 0x1f3ff), // skin tone modifiers.
+    (0xe0020, 0xe007f), // tags, as used in subdivision flags.
+    (0xe0100, 0xe01ef),
+];
+
+const SPACING_MARK: &[(u32, u32)] = &[
+    (0x0903, 0x0903),
+    (0x093b, 0x093b),
+    (0x093e, 0x0940),
+    (0x0949, 0x094c),
+    (0x094e, 0x094f),
+    (0x0e33, 0x0e33),
+];
+
+const PICTOGRAPHIC: &[(u32, u32)] = &[
+    (0x00a9, 0x00a9),
+    (0x00ae, 0x00ae),
+    (0x203c, 0x203c),
+    (0x2049, 0x2049),
+    (0x2122, 0x2122),
+    (0x2139, 0x2139),
+    (0x2194, 0x21aa),
+    (0x231a, 0x23ff),
+    (0x25aa, 0x25fe),
+    (0x2600, 0x27bf),
+    (0x2934, 0x2935),
+    (0x2b05, 0x2b55),
+    (0x3030, 0x3030),
+    (0x303d, 0x303d),
+    (0x3297, 0x3299),
+    (0x1f000, 0x1faff),
+    (0x1fc00, 0x1fffd),
+];
+
+fn in_ranges(ranges: &[(u32, u32)], c: char) -> bool {
+    let c = c as u32;
+    ranges
+        .binary_search_by(|(start, end)| {
+            if *end < c {
+                Ordering::Less
+            } else if *start > c {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+fn category(c: char) -> Category {
+    match c as u32 {
+        0x0d => Category::Cr,
+        0x0a => Category::Lf,
+        0x200d => Category::Zwj,
+        0x1100..=0x115f | 0xa960..=0xa97f => Category::L,
+        0x1160..=0x11a7 | 0xd7b0..=0xd7c6 => Category::V,
+        0x11a8..=0x11ff | 0xd7cb..=0xd7fb => Category::T,
+        0xac00..=0xd7a3 if (c as u32 - 0xac00).is_multiple_of(28) => Category::Lv,
+        0xac00..=0xd7a3 => Category::Lvt,
+        0x1f1e6..=0x1f1ff => Category::RegionalIndicator,
+        0xad | 0x2028 | 0x2029 => Category::Control,
+        _ if c.is_control() => Category::Control,
+        _ if in_ranges(EXTEND, c) => Category::Extend,
+        _ if in_ranges(SPACING_MARK, c) => Category::SpacingMark,
+        _ if in_ranges(PICTOGRAPHIC, c) => Category::Pictographic,
+        _ => Category::Other,
+    }
+}
+
+// splits a string into extended grapheme clusters, the characters a reader would count.
+#[derive(Clone, Debug)]
+pub struct Graphemes<'a> {
+    rest: &'a str,
+}
+
+pub fn graphemes(string: &str) -> Graphemes<'_> {
+    Graphemes { rest: string }
+}
+
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let mut chars = self.rest.char_indices();
+        let mut previous = category(chars.next()?.1);
+        // within a pictograph followed by any extending marks, and whether a joiner ended it.
+        let mut pictographic = previous == Category::Pictographic;
+        let mut regional_indicators = usize::from(previous == Category::RegionalIndicator);
+        let mut end = self.rest.len();
+
+        for (index, c) in chars {
+            let next = category(c);
+            let joined = match (previous, next) {
+                (Category::Cr, Category::Lf) => true,
+                (Category::Cr, _) | (Category::Lf, _) | (Category::Control, _) => false,
+                (_, Category::Cr) | (_, Category::Lf) | (_, Category::Control) => false,
+                (Category::L, Category::L)
+                | (Category::L, Category::V)
+                | (Category::L, Category::Lv)
+                | (Category::L, Category::Lvt) => true,
+                (Category::Lv, Category::V)
+                | (Category::Lv, Category::T)
+                | (Category::V, Category::V)
+                | (Category::V, Category::T) => true,
+                (Category::Lvt, Category::T) | (Category::T, Category::T) => true,
+                (_, Category::Extend) | (_, Category::Zwj) | (_, Category::SpacingMark) => true,
+                (Category::Zwj, Category::Pictographic) => pictographic,
+                (Category::RegionalIndicator, Category::RegionalIndicator) => {
+                    regional_indicators % 2 == 1
+                }
+                _ => false,
+            };
+            if !joined {
+                end = index;
+                break;
+            }
+
+            pictographic = match next {
+                Category::Pictographic => true,
+                Category::Extend | Category::Zwj => pictographic && previous != Category::Zwj,
+                _ => false,
+            };
+            if next == Category::RegionalIndicator {
+                regional_indicators += 1;
+            }
+            previous = next;
+        }
+
+        let (cluster, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(cluster)
+    }
+}
+
+impl FusedIterator for Graphemes<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(string: &str) -> Vec<&str> {
+        graphemes(string).collect()
+    }
+
+    #[test]
+    fn test_combining_marks() {
+        assert_eq!(split("abc"), ["a", "b", "c"]);
+        assert_eq!(split("e\u{301}te\u{301}"), ["e\u{301}", "t", "e\u{301}"]);
+        assert_eq!(split("\u{e9}t\u{e9}"), ["\u{e9}", "t", "\u{e9}"]);
+        assert_eq!(split("a\r\nb"), ["a", "\r\n", "b"]);
+        assert_eq!(
+            split("\u{928}\u{92e}\u{938}\u{94d}\u{924}\u{947}"),
+            ["\u{928}", "\u{92e}", "\u{938}\u{94d}", "\u{924}\u{947}"]
+        );
+        assert_eq!(
+            split("\u{1100}\u{1161}\u{11a8}\u{d55c}"),
+            ["\u{1100}\u{1161}\u{11a8}", "\u{d55c}"]
+        );
+        assert!(split("").is_empty());
+    }
+
+    #[test]
+    fn test_emoji() {
+        // a family joined with zero width joiners, a skin tone, and two flags.
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+        assert_eq!(split(family), [family]);
+        assert_eq!(split("\u{1f44d}\u{1f3fd}!"), ["\u{1f44d}\u{1f3fd}", "!"]);
+        assert_eq!(
+            split("\u{1f1ec}\u{1f1e7}\u{1f1eb}\u{1f1f7}\u{1f1ea}"),
+            ["\u{1f1ec}\u{1f1e7}", "\u{1f1eb}\u{1f1f7}", "\u{1f1ea}"]
+        );
+        assert_eq!(split("a\u{200d}\u{1f467}"), ["a\u{200d}", "\u{1f467}"]);
+    }
+}